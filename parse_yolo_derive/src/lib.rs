@@ -1,11 +1,11 @@
 use proc_macro::TokenStream;
-use proc_macro2::Ident;
+use proc_macro2::{Ident, Span};
 
 use quote::quote;
 use syn::{Attribute, Data, DataEnum, DataStruct, DeriveInput, Field, Fields, GenericParam, Generics, LitStr, Meta, MetaList, parse_macro_input};
 use syn::spanned::Spanned;
 
-#[proc_macro_derive(ParseYolo, attributes(pattern, separator))]
+#[proc_macro_derive(ParseYolo, attributes(pattern, regex, separator, flexible_whitespace))]
 pub fn parse_yolo_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -17,9 +17,16 @@ pub fn parse_yolo_derive(input: TokenStream) -> TokenStream {
 }
 
 fn derive_struct(input: &DeriveInput, struct_data: &DataStruct) -> TokenStream {
-    let pattern = get_pattern(&input.attrs).unwrap();
     if let Fields::Named(fields) = &struct_data.fields {
-        let body = pattern_parsing_body(&pattern, fields.named.iter(), quote!(Self));
+        let body = if let Some(regex) = get_lit_attr(&input.attrs, "regex") {
+            match regex_parsing_body(&regex, fields.named.iter().collect(), quote!(Self)) {
+                Ok(body) => body,
+                Err(error) => return error.to_compile_error().into(),
+            }
+        } else {
+            let pattern = get_pattern(&input.attrs).unwrap();
+            pattern_parsing_body(&pattern, has_flexible_whitespace(&input.attrs), fields.named.iter(), quote!(Self))
+        };
         generate_impl(&input.ident, &input.generics, body)
     } else {
         syn::Error::new(input.span(), "Only named fields are currently supported").to_compile_error().into()
@@ -28,17 +35,27 @@ fn derive_struct(input: &DeriveInput, struct_data: &DataStruct) -> TokenStream {
 
 fn derive_enum(input: &DeriveInput, struct_data: &DataEnum) -> TokenStream {
     let mut body = Vec::new();
+    let mut variant_names = Vec::new();
     for variant in &struct_data.variants {
         let pattern = get_pattern(&variant.attrs);
         if !body.is_empty() {
             body.push(quote!(else));
         }
         let variant_name = &variant.ident;
+        variant_names.push(variant_name.to_string());
+        if let Some(regex) = get_lit_attr(&variant.attrs, "regex") {
+            let lambda_body = match regex_parsing_body(&regex, variant.fields.iter().collect(), quote!(Self::#variant_name)) {
+                Ok(body) => body,
+                Err(error) => return error.to_compile_error().into(),
+            };
+            body.push(quote!(if let Ok(x) = stream.try_parse(|stream| { #(#lambda_body)* }) { Ok(x) }));
+            continue;
+        }
         match &variant.fields {
             Fields::Named(_) => panic!("Named struct fields not supported"),
             Fields::Unnamed(unnamed) => {
                 if let Some(pattern) = pattern {
-                    let lambda_body = pattern_parsing_body(&pattern, unnamed.unnamed.iter(), quote!(Self::#variant_name));
+                    let lambda_body = pattern_parsing_body(&pattern, has_flexible_whitespace(&variant.attrs), unnamed.unnamed.iter(), quote!(Self::#variant_name));
                     body.push(quote!(if let Ok(x) = stream.try_parse(|stream| { #(#lambda_body)* }) { Ok(x) }));
                 } else {
                     if unnamed.unnamed.len() == 1 {
@@ -54,7 +71,7 @@ fn derive_enum(input: &DeriveInput, struct_data: &DataEnum) -> TokenStream {
             }
         };
     }
-    body.push(quote!(else { Err(()) }));
+    body.push(quote!(else { Err(stream.error(::advent_of_rust::input::Expected::OneOf(&[#(#variant_names),*]))) }));
 
     generate_impl(&input.ident, &input.generics, body)
 }
@@ -67,14 +84,15 @@ fn generate_impl(target_name: &Ident, generics: &Generics, body: Vec<proc_macro2
             None
         })
         .collect();
+    // The input lifetime is needed to name the error type, so types without one get a fresh one.
     let (impl_lifetime, lifetime_params) = if lifetime_params.is_empty() {
-        (quote!(<'_>), quote!())
+        (quote!(<'input>), quote!())
     } else {
         (quote!(<#(#lifetime_params)*>), quote!(<#(#lifetime_params)*>))
     };
     let gen = quote! {
-        impl #lifetime_params crate::input::ParseYolo #impl_lifetime for #target_name #lifetime_params {
-            fn parse_from_stream(stream: &mut crate::input::ParseStream #lifetime_params) -> Result<Self, ()> {
+        impl #impl_lifetime ::advent_of_rust::input::ParseYolo #impl_lifetime for #target_name #lifetime_params {
+            fn parse_from_stream(stream: &mut ::advent_of_rust::input::ParseStream #impl_lifetime) -> Result<Self, ::advent_of_rust::input::ParseError #impl_lifetime> {
                 #(#body)*
             }
         }
@@ -82,11 +100,11 @@ fn generate_impl(target_name: &Ident, generics: &Generics, body: Vec<proc_macro2
     gen.into()
 }
 
-fn pattern_parsing_body<'a, I: Iterator<Item=&'a Field>>(pattern: &str, mut fields: I, constructor: proc_macro2::TokenStream) -> Vec<proc_macro2::TokenStream> {
+fn pattern_parsing_body<'a, I: Iterator<Item=&'a Field>>(pattern: &str, flexible_whitespace: bool, mut fields: I, constructor: proc_macro2::TokenStream) -> Vec<proc_macro2::TokenStream> {
     let mut body = Vec::new();
     let mut field_names = Vec::new();
     let mut named = false;
-    for (i, part) in split_pattern(&pattern).into_iter().enumerate() {
+    for (i, part) in split_pattern(pattern).into_iter().enumerate() {
         if part == "{}" {
             let field = fields.next().unwrap();
             let field_name = if let Some(name) = &field.ident {
@@ -103,6 +121,8 @@ fn pattern_parsing_body<'a, I: Iterator<Item=&'a Field>>(pattern: &str, mut fiel
             };
             body.push(quote!(let #field_name = #function_call?;));
             field_names.push(field_name);
+        } else if flexible_whitespace {
+            body.extend(literal_parsing_flexible(part));
         } else {
             body.push(quote!(stream.expect(#part)?;));
         }
@@ -115,6 +135,60 @@ fn pattern_parsing_body<'a, I: Iterator<Item=&'a Field>>(pattern: &str, mut fiel
     body
 }
 
+/// Matches the regex where the stream is, and parses each group into the next field. The regex is
+/// checked here, but compiled again the first time it is needed.
+fn regex_parsing_body(regex: &LitStr, fields: Vec<&Field>, constructor: proc_macro2::TokenStream) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let pattern = regex.value();
+    let anchored = format!("^(?:{})", pattern);
+    let compiled = regex::bytes::Regex::new(&anchored)
+        .map_err(|error| syn::Error::new(regex.span(), format!("Invalid regex: {}", error)))?;
+    if compiled.captures_len() - 1 != fields.len() {
+        return Err(syn::Error::new(
+            regex.span(),
+            format!("The regex has {} groups, but there are {} fields", compiled.captures_len() - 1, fields.len()),
+        ));
+    }
+    // Hygienic, so that a field called `groups` does not shadow it
+    let groups = Ident::new("groups", Span::mixed_site());
+    let mut body = vec![quote! {
+        let mut #groups = {
+            static REGEX: ::std::sync::OnceLock<::advent_of_rust::regex::bytes::Regex> = ::std::sync::OnceLock::new();
+            let regex = REGEX.get_or_init(|| ::advent_of_rust::regex::bytes::Regex::new(#anchored).unwrap());
+            stream.match_regex(regex, #pattern)?.into_iter()
+        };
+    }];
+    let field_names: Vec<_> = fields.iter().enumerate()
+        .map(|(i, field)| field.ident.clone().unwrap_or_else(|| Ident::new(&format!("x{}", i), field.span())))
+        .collect();
+    for field_name in &field_names {
+        body.push(quote!(let #field_name = stream.parse_group(#groups.next().unwrap())?;));
+    }
+    body.push(match fields.first().map(|field| field.ident.is_some()) {
+        None => quote!(Ok(#constructor)),
+        Some(true) => quote!(Ok(#constructor { #(#field_names, )* })),
+        Some(false) => quote!(Ok(#constructor( #(#field_names, )* ))),
+    });
+    Ok(body)
+}
+
+/// Every run of spaces in the literal matches one or more whitespace characters.
+fn literal_parsing_flexible(literal: &str) -> Vec<proc_macro2::TokenStream> {
+    let mut result = Vec::new();
+    let mut rest = literal;
+    while !rest.is_empty() {
+        let text_length = rest.find(' ').unwrap_or(rest.len());
+        if text_length == 0 {
+            rest = rest.trim_start_matches(' ');
+            result.push(quote!(stream.expect_whitespace()?;));
+        } else {
+            let text = &rest[..text_length];
+            rest = &rest[text_length..];
+            result.push(quote!(stream.expect(#text)?;));
+        }
+    }
+    result
+}
+
 fn split_pattern(pattern: &str) -> Vec<&str> {
     let mut result = Vec::new();
     for part in pattern.split_inclusive("{}") {
@@ -130,17 +204,24 @@ fn split_pattern(pattern: &str) -> Vec<&str> {
     result
 }
 
+fn has_flexible_whitespace(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attribute| attribute.path().is_ident("flexible_whitespace"))
+}
+
 fn get_pattern(attrs: &[Attribute]) -> Option<String> {
     get_attr(attrs, "pattern")
 }
 
 fn get_attr(attrs: &[Attribute], name: &str) -> Option<String> {
+    get_lit_attr(attrs, name).map(|literal| literal.value())
+}
+
+fn get_lit_attr(attrs: &[Attribute], name: &str) -> Option<LitStr> {
     attrs.iter()
         .filter_map(|attribute|
             if let Meta::List(MetaList { path, .. }) = &attribute.meta {
                 if path.is_ident(name) {
-                    let pattern: LitStr = attribute.parse_args().ok()?;
-                    Some(pattern.value())
+                    attribute.parse_args().ok()
                 } else {
                     None
                 }
@@ -0,0 +1,103 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Whether the counting allocator is installed, which is what the `alloc-profile` feature does.
+pub const ENABLED: bool = cfg!(feature = "alloc-profile");
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Bytes allocated in total, including reallocations.
+    pub allocated: usize,
+    pub allocations: usize,
+    /// The most bytes that were live at once, on top of those live when counting started.
+    pub peak: usize,
+}
+
+/// The system allocator, counting what goes through it.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn record_allocation(size: usize) {
+        ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(live, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            Self::record_allocation(layout.size());
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc_zeroed(layout);
+        if !pointer.is_null() {
+            Self::record_allocation(layout.size());
+        }
+        pointer
+    }
+
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = System.realloc(pointer, layout, new_size);
+        if !new_pointer.is_null() {
+            LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+            Self::record_allocation(new_size);
+        }
+        new_pointer
+    }
+}
+
+/// Counts the allocations made while running `f`, or returns `None` if the counting allocator is not installed.
+/// Allocations of other threads are counted too.
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> Option<AllocStats> {
+    if !ENABLED {
+        return None;
+    }
+    let allocated = ALLOCATED.load(Ordering::Relaxed);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let live = LIVE.load(Ordering::Relaxed);
+    PEAK.store(live, Ordering::Relaxed);
+    drop(f());
+    Some(AllocStats {
+        allocated: ALLOCATED.load(Ordering::Relaxed) - allocated,
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        peak: PEAK.load(Ordering::Relaxed).saturating_sub(live),
+    })
+}
+
+
+#[cfg(all(test, feature = "alloc-profile"))]
+mod tests {
+    use std::hint::black_box;
+
+    use super::*;
+
+    #[test]
+    fn allocations_are_counted() {
+        let result = measure(|| {
+            let first = black_box(vec![0u8; 1000]);
+            drop(first);
+            let second = black_box(vec![0u8; 600]);
+            black_box(Vec::<u64>::with_capacity(50)).len() + second.len()
+        }).unwrap();
+
+        assert!(result.allocated >= 2000);
+        assert!(result.allocations >= 3);
+        assert!(result.peak >= 1000 && result.peak < 2000);
+    }
+}
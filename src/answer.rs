@@ -0,0 +1,108 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Debug)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    NoPart2,
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Number(first), Answer::Number(second)) => first == second,
+            (Answer::Text(first), Answer::Text(second)) => first == second,
+            (Answer::Number(number), Answer::Text(text)) | (Answer::Text(text), Answer::Number(number)) => *text == number.to_string(),
+            (Answer::NoPart2, Answer::NoPart2) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{}", number),
+            Answer::Text(text) => f.write_str(text),
+            Answer::NoPart2 => f.write_str("-"),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, ()> {
+        Ok(if value == "-" {
+            Answer::NoPart2
+        } else if let Ok(number) = value.parse() {
+            Answer::Number(number)
+        } else {
+            Answer::Text(value.to_owned())
+        })
+    }
+}
+
+macro_rules! number_answer {
+    ($($number_type:ty),*) => {
+        $(
+        impl From<$number_type> for Answer {
+            fn from(value: $number_type) -> Self {
+                Answer::Number(value as i128)
+            }
+        }
+        )*
+    };
+}
+
+number_answer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_owned())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_of_different_types_are_equal() {
+        assert_eq!(Answer::from(42u32), Answer::from(42usize));
+        assert_eq!(Answer::from(-7i64), Answer::from(-7isize));
+    }
+
+    #[test]
+    fn parsed_answers_equal_computed_ones() {
+        assert_eq!("1709".parse::<Answer>(), Ok(Answer::from(1709u64)));
+        assert_eq!("-12".parse::<Answer>(), Ok(Answer::from(-12i64)));
+        assert_eq!("99598963999971".parse::<Answer>(), Ok(Answer::from(99598963999971u64)));
+        assert_eq!("ABCD".parse::<Answer>(), Ok(Answer::from("ABCD")));
+        assert_eq!("-".parse::<Answer>(), Ok(Answer::NoPart2));
+    }
+
+    #[test]
+    fn numeric_text_equals_number() {
+        assert_eq!(Answer::from("93151411711211".to_owned()), Answer::from(93151411711211u64));
+        assert_ne!(Answer::from("0042".to_owned()), Answer::from(42u64));
+        assert_ne!(Answer::NoPart2, Answer::from(0u64));
+    }
+
+    #[test]
+    fn display_matches_the_underlying_value() {
+        assert_eq!(Answer::from(18446744073709551615u64).to_string(), "18446744073709551615");
+        assert_eq!(Answer::from("#..#\n.##.").to_string(), "#..#\n.##.");
+        assert_eq!(Answer::NoPart2.to_string(), "-");
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+
+use crate::answer::Answer;
+use crate::registry::SolutionKey;
+
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KnownAnswer {
+    pub input_hash: u64,
+    pub answer: Answer,
+}
+
+pub struct AnswerStore {
+    answers: BTreeMap<SolutionKey, KnownAnswer>,
+}
+
+impl AnswerStore {
+    pub fn load(path: &str) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Self::parse(&contents)
+                .map_err(|line| io::Error::new(ErrorKind::InvalidData, format!("Malformed line in {}: {}", path, line))),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Self { answers: BTreeMap::new() }),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.serialize())
+    }
+
+    pub fn get(&self, key: SolutionKey) -> Option<&KnownAnswer> {
+        self.answers.get(&key)
+    }
+
+    pub fn insert(&mut self, key: SolutionKey, known_answer: KnownAnswer) {
+        self.answers.insert(key, known_answer);
+    }
+
+    fn parse(contents: &str) -> Result<Self, &str> {
+        let mut answers = BTreeMap::new();
+        for line in contents.lines().filter(|line| !line.is_empty()) {
+            let (key, known_answer) = Self::parse_line(line).ok_or(line)?;
+            answers.insert(key, known_answer);
+        }
+        Ok(Self { answers })
+    }
+
+    fn parse_line(line: &str) -> Option<(SolutionKey, KnownAnswer)> {
+        let mut fields = line.splitn(5, ' ');
+        let key = SolutionKey {
+            year: fields.next()?.parse().ok()?,
+            day: fields.next()?.parse().ok()?,
+            part: fields.next()?.parse().ok()?,
+        };
+        let input_hash = u64::from_str_radix(fields.next()?, 16).ok()?;
+        let answer = fields.next()?.replace("\\n", "\n").parse().ok()?;
+        Some((key, KnownAnswer { input_hash, answer }))
+    }
+
+    fn serialize(&self) -> String {
+        let mut result = String::new();
+        for (key, known_answer) in &self.answers {
+            writeln!(
+                result,
+                "{} {:02} {} {:016x} {}",
+                key.year,
+                key.day,
+                key.part,
+                known_answer.input_hash,
+                escape(&known_answer.answer),
+            ).unwrap();
+        }
+        result
+    }
+}
+
+/// FNV-1a, which unlike the std hashers is guaranteed to stay the same between Rust versions.
+pub fn hash_input(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+pub fn escape(answer: &Answer) -> String {
+    answer.to_string().replace('\n', "\\n")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialized_store_can_be_parsed_back() {
+        let mut store = AnswerStore { answers: BTreeMap::new() };
+        store.insert(key(2021, 13, 2), KnownAnswer { input_hash: 0xabc, answer: Answer::from("#..#\n.##.") });
+        store.insert(key(2021, 1, 1), KnownAnswer { input_hash: 0xdef, answer: Answer::from(1709u64) });
+
+        let serialized = store.serialize();
+        let parsed = AnswerStore::parse(&serialized).unwrap();
+
+        assert_eq!(serialized, "2021 01 1 0000000000000def 1709\n2021 13 2 0000000000000abc #..#\\n.##.\n");
+        assert_eq!(parsed.get(key(2021, 13, 2)), store.get(key(2021, 13, 2)));
+        assert_eq!(parsed.get(key(2021, 1, 1)), store.get(key(2021, 1, 1)));
+    }
+
+    #[test]
+    fn malformed_lines_are_rejected() {
+        let result = AnswerStore::parse("2021 01 1 0000000000000def 1709\n2021 01 2 1709\n");
+
+        assert_eq!(result.err(), Some("2021 01 2 1709"));
+    }
+
+    #[test]
+    fn input_hash_is_stable() {
+        assert_eq!(hash_input(b""), 0xcbf29ce484222325);
+        assert_eq!(hash_input(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    fn key(year: u16, day: u8, part: u8) -> SolutionKey {
+        SolutionKey { year, day, part }
+    }
+}
@@ -2,6 +2,7 @@ use std::cmp::max;
 use std::fmt::{Display, Formatter};
 use std::ops::{Add, Index, IndexMut, Sub};
 
+use bstr::ByteSlice;
 use derive_new::new;
 
 use crate::input::InputData;
@@ -14,8 +15,13 @@ pub struct Array2d<T> {
 
 impl<T> Array2d<T> {
     pub fn from_transformed_input<F: Fn(u8) -> T>(input: &InputData, transformation: F) -> Self {
-        let mut values = Vec::with_capacity(input.len());
-        let mut lines = input.lines().peekable();
+        Self::from_transformed_bytes(input.raw(), transformation)
+    }
+
+    /// One row per line.
+    pub fn from_transformed_bytes<F: Fn(u8) -> T>(data: &[u8], transformation: F) -> Self {
+        let mut values = Vec::with_capacity(data.len());
+        let mut lines = data.lines().peekable();
         let num_columns = lines.peek().unwrap().len();
         let mut num_rows = 0;
         for line in lines {
@@ -35,7 +41,7 @@ impl<T> Array2d<T> {
         self.num_columns
     }
 
-    pub fn rows(&self) -> impl Iterator<Item=ArraySlice<T>> {
+    pub fn rows(&self) -> impl Iterator<Item=ArraySlice<'_, T>> {
         (0..self.num_rows).map(|row|
             ArraySlice {
                 array: self,
@@ -46,7 +52,7 @@ impl<T> Array2d<T> {
         )
     }
 
-    pub fn columns(&self) -> impl Iterator<Item=ArraySlice<T>> {
+    pub fn columns(&self) -> impl Iterator<Item=ArraySlice<'_, T>> {
         (0..self.num_columns).map(|column|
             ArraySlice {
                 array: self,
@@ -174,7 +180,7 @@ impl<T, LI: IntoIterator<Item=T>> FromIterator<LI> for Array2d<T> {
         let mut values: Vec<T> = Vec::with_capacity(max(iterator.size_hint().0, 4));
         let mut num_rows = 0;
         for row in iterator {
-            values.extend(row.into_iter());
+            values.extend(row);
             num_rows += 1;
         }
         Array2d {
@@ -1,75 +1,631 @@
-use std::path::Path;
-use crate::input::InputData;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
 
-const W_DAY: usize = 10;
-const W_PART: usize = 10;
+use clap::ValueEnum;
+use itertools::Itertools;
 
-fn mean(numbers: &[f64]) -> f64 {
-    numbers.iter().sum::<f64>() / numbers.len() as f64
+use crate::alloc_counter;
+use crate::alloc_counter::AllocStats;
+use crate::isolation::Failure;
+use crate::registry::SolutionKey;
+
+pub const README_FILE: &str = "README.md";
+
+const W_DAY: usize = 8;
+const W_PART: usize = 6;
+const W_STAT: usize = 10;
+
+/// How long a solution runs before measuring starts, to warm up caches and the branch predictor.
+const WARMUP: Duration = Duration::from_millis(100);
+/// How long measuring a single part should take, which decides the number of iterations.
+const BUDGET: Duration = Duration::from_millis(500);
+const MAX_ITERATIONS: usize = 100_000;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
 }
 
-fn measure_run<S: ?Sized, T, F: Fn(&S) -> T>(f: &F, input: &S) -> f64 {
-    let start = std::time::SystemTime::now();
-    _ = f(input);
-    let duration = start.elapsed().unwrap();
-    duration.as_secs_f64()
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub iterations: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+    pub p95: Duration,
 }
 
-pub fn benchmark_run<S: ?Sized, T, F: Fn(&S) -> T>(f: F, input: &S) -> f64 {
-    let first_run = measure_run(&f, input);
-    let n = (1. / first_run) as i32;
-    if n <= 1 || first_run < 0.000001 {
-        return first_run;
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let n = samples.len();
+        let nanos = samples.iter().map(|sample| sample.as_nanos() as f64).collect::<Vec<_>>();
+        let mean = nanos.iter().sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            nanos.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.
+        };
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+        Self {
+            iterations: n,
+            min: samples[0],
+            median,
+            mean: Duration::from_nanos(mean.round() as u64),
+            stddev: Duration::from_nanos(variance.sqrt().round() as u64),
+            p95: samples[(n * 95).div_ceil(100) - 1],
+        }
     }
-    let mut run_times = vec![];
-    for _ in 0..n {
-        run_times.push(measure_run(&f, input));
+}
+
+fn measure_run<S: ?Sized, T, F: Fn(&S) -> T>(f: &F, input: &S) -> Duration {
+    let start = Instant::now();
+    black_box(f(black_box(input)));
+    start.elapsed()
+}
+
+/// Runs `f` for `WARMUP`, then as many times as fit in `BUDGET` judging by the warmup runs, but at
+/// least once.
+pub fn benchmark_run<S: ?Sized, T, F: Fn(&S) -> T>(f: F, input: &S) -> Stats {
+    let warmup_start = Instant::now();
+    let mut warmup_runs = 0;
+    while warmup_runs == 0 || warmup_start.elapsed() < WARMUP {
+        measure_run(&f, input);
+        warmup_runs += 1;
     }
-    mean(&run_times)
+    let estimate = warmup_start.elapsed() / warmup_runs;
+    let iterations = (BUDGET.as_nanos() / estimate.as_nanos().max(1)) as usize;
+    let samples = (0..iterations.clamp(1, MAX_ITERATIONS))
+        .map(|_| measure_run(&f, input))
+        .collect();
+    Stats::from_samples(samples)
+}
+
+/// Runs `f` once more to count its allocations, if the counting allocator is installed.
+pub fn profile_allocations<S: ?Sized, T, F: Fn(&S) -> T>(f: F, input: &S) -> Option<AllocStats> {
+    alloc_counter::measure(|| f(black_box(input)))
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    format_scaled(duration.as_nanos() as f64, &["ns", "µs", "ms", "s"])
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+    format_scaled(bytes as f64, &["B", "kB", "MB", "GB"])
 }
 
-pub fn print_header() {
+/// Formats with three significant digits in the largest unit that keeps the value at least 1,
+/// each unit being a thousand of the previous one.
+fn format_scaled(mut value: f64, units: &[&str]) -> String {
+    let mut unit = 0;
+    while value >= 1e3 && unit + 1 < units.len() {
+        value /= 1e3;
+        unit += 1;
+    }
+    loop {
+        let precision = if value >= 99.95 || unit == 0 {
+            0
+        } else if value >= 9.995 {
+            1
+        } else {
+            2
+        };
+        let scale = 10f64.powi(precision as i32);
+        let rounded = (value * scale).round() / scale;
+        // 999.6 rounds up to 1000, which belongs in the next unit
+        if rounded < 1e3 || unit + 1 == units.len() {
+            return format!("{:.*} {}", precision, rounded, units[unit]);
+        }
+        value = rounded / 1e3;
+        unit += 1;
+    }
+}
+
+pub fn print_header(year: u16) {
+    println!("year {}", year);
+    println!();
     print!("{:<w$}", "day", w = W_DAY);
-    print!("{:<w$}", "part 1", w = W_PART);
-    print!("{:<w$}", "part 2", w = W_PART);
+    print!("{:<w$}", "part", w = W_PART);
+    for column in ["min", "median", "mean", "stddev", "p95"] {
+        print!("{:>w$}", column, w = W_STAT);
+    }
+    print!("{:>w$}", "runs", w = W_STAT);
+    let mut num_columns = 6;
+    if alloc_counter::ENABLED {
+        for column in ["allocated", "allocs", "peak"] {
+            print!("{:>w$}", column, w = W_STAT);
+        }
+        num_columns += 3;
+    }
     println!();
-    println!("{:-<w$}", "", w = W_DAY + W_PART * 2);
+    println!("{:-<w$}", "", w = W_DAY + W_PART + W_STAT * num_columns);
 }
 
-pub fn print_day(day: u8, p1: f64, p2: f64) {
-    print!("{:<w$}", format!("day {:02}", day), w = W_DAY);
+/// Prints a row for each measurement of a day; part 0 is the parse step.
+pub fn print_day(day: u8, results: &[Result<Measurement, Failed>]) {
+    for (i, result) in results.iter().enumerate() {
+        let key = match result {
+            Ok(measurement) => measurement.key,
+            Err(failed) => failed.key,
+        };
+        let day_label = if i == 0 { format!("day {:02}", day) } else { String::new() };
+        let part_label = if key.part == PARSE_PART { "parse".to_owned() } else { key.part.to_string() };
+        print!("{:<w$}", day_label, w = W_DAY);
+        print!("{:<w$}", part_label, w = W_PART);
+        let measurement = match result {
+            Ok(measurement) => measurement,
+            Err(failed) => {
+                // Parse errors go on to show the offending line, which doesn't fit in the table
+                println!("{}", failed.failure.to_string().lines().next().unwrap_or_default());
+                continue;
+            }
+        };
+        let stats = &measurement.stats;
+        for value in [stats.min, stats.median, stats.mean, stats.stddev, stats.p95] {
+            print!("{:>w$}", format_duration(value), w = W_STAT);
+        }
+        print!("{:>w$}", stats.iterations, w = W_STAT);
+        if let Some(alloc) = &measurement.alloc {
+            print!("{:>w$}", format_bytes(alloc.allocated), w = W_STAT);
+            print!("{:>w$}", alloc.allocations, w = W_STAT);
+            print!("{:>w$}", format_bytes(alloc.peak), w = W_STAT);
+        }
+        println!();
+    }
+}
+
+/// The part number under which the parse step of a `Solution` is measured. The parts of such days
+/// are measured without parsing.
+pub const PARSE_PART: u8 = 0;
 
-    let mut p1_dur = format!("{:.3}", p1 * 1000.).to_string();
-    p1_dur = format!("{} ms", &p1_dur[..5]);
-    print!("{:<w$}", p1_dur, w = W_PART);
+/// A part that could not be measured.
+#[derive(Debug)]
+pub struct Failed {
+    pub key: SolutionKey,
+    pub failure: Failure,
+}
 
-    let mut p2_dur = format!("{:.3}", p2 * 1000.).to_string();
-    p2_dur = format!("{} ms", &p2_dur[..5]);
-    println!("{:<w$}", p2_dur, w = W_PART);
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Measurement {
+    pub key: SolutionKey,
+    pub stats: Stats,
+    /// Only known when built with the `alloc-profile` feature.
+    pub alloc: Option<AllocStats>,
 }
 
-#[macro_export]
-macro_rules! benchmark_all {
-    ($($day:ident),*) => {{
-        $crate::benchmark::print_header();
-        $(
-        let input = $crate::benchmark::find_input(&stringify!($day));
+pub fn to_json(measurements: &[Measurement]) -> String {
+    let mut result = String::from("[\n");
+    for (i, measurement) in measurements.iter().enumerate() {
+        let SolutionKey { year, day, part } = measurement.key;
+        let stats = &measurement.stats;
+        write!(
+            result,
+            "  {{\"year\": {}, \"day\": {}, \"part\": {}, \"iterations\": {}, \"min_ns\": {}, \"median_ns\": {}, \"mean_ns\": {}, \"stddev_ns\": {}, \"p95_ns\": {}",
+            year, day, part, stats.iterations,
+            stats.min.as_nanos(), stats.median.as_nanos(), stats.mean.as_nanos(), stats.stddev.as_nanos(), stats.p95.as_nanos(),
+        ).unwrap();
+        if let Some(alloc) = &measurement.alloc {
+            write!(
+                result,
+                ", \"allocated_bytes\": {}, \"allocations\": {}, \"peak_bytes\": {}",
+                alloc.allocated, alloc.allocations, alloc.peak,
+            ).unwrap();
+        }
+        result.push('}');
+        result.push_str(if i + 1 < measurements.len() { ",\n" } else { "\n" });
+    }
+    result.push_str("]\n");
+    result
+}
 
-        let p1_duration = $crate::benchmark::benchmark_run($crate::year2021::$day::part_1, &input);
-        let p2_duration = $crate::benchmark::benchmark_run($crate::year2021::$day::part_2, &input);
+const CSV_HEADER: &str = "year,day,part,iterations,min_ns,median_ns,mean_ns,stddev_ns,p95_ns,allocated_bytes,allocations,peak_bytes";
 
-        $crate::benchmark::print_day(stringify!($day).to_string()[3..].parse().unwrap(), p1_duration, p2_duration);
-        )*
-    }};
+/// Also the format of baseline files, so that they can be read back.
+pub fn to_csv(measurements: &[Measurement]) -> String {
+    let mut result = format!("{}\n", CSV_HEADER);
+    for measurement in measurements {
+        let SolutionKey { year, day, part } = measurement.key;
+        let stats = &measurement.stats;
+        write!(
+            result,
+            "{},{},{},{},{},{},{},{},{}",
+            year, day, part, stats.iterations,
+            stats.min.as_nanos(), stats.median.as_nanos(), stats.mean.as_nanos(), stats.stddev.as_nanos(), stats.p95.as_nanos(),
+        ).unwrap();
+        match &measurement.alloc {
+            Some(alloc) => writeln!(result, ",{},{},{}", alloc.allocated, alloc.allocations, alloc.peak).unwrap(),
+            None => result.push_str(",,,\n"),
+        }
+    }
+    result
+}
+
+pub fn parse_csv(contents: &str) -> Result<Vec<Measurement>, &str> {
+    contents.lines()
+        .filter(|line| !line.is_empty() && !line.starts_with("year,"))
+        .map(|line| parse_csv_line(line).ok_or(line))
+        .collect()
+}
+
+fn parse_csv_line(line: &str) -> Option<Measurement> {
+    let mut fields = line.split(',');
+    let key = SolutionKey {
+        year: fields.next()?.parse().ok()?,
+        day: fields.next()?.parse().ok()?,
+        part: fields.next()?.parse().ok()?,
+    };
+    let iterations = fields.next()?.parse().ok()?;
+    let mut durations = [Duration::ZERO; 5];
+    for duration in durations.iter_mut() {
+        *duration = Duration::from_nanos(fields.next()?.parse().ok()?);
+    }
+    let [min, median, mean, stddev, p95] = durations;
+    // Baselines saved before allocations were profiled have no allocation columns at all.
+    let alloc = match (fields.next(), fields.next(), fields.next()) {
+        (None, _, _) | (Some(""), Some(""), Some("")) => None,
+        (Some(allocated), Some(allocations), Some(peak)) => Some(AllocStats {
+            allocated: allocated.parse().ok()?,
+            allocations: allocations.parse().ok()?,
+            peak: peak.parse().ok()?,
+        }),
+        _ => return None,
+    };
+    Some(Measurement { key, stats: Stats { iterations, min, median, mean, stddev, p95 }, alloc })
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Comparison {
+    pub key: SolutionKey,
+    pub baseline: Duration,
+    pub current: Duration,
+    /// Relative change of the median, in percent.
+    pub change: f64,
+    pub regressed: bool,
+}
+
+/// Compares medians with the baseline; a part regressed if it got slower by more than `threshold` percent.
+pub fn compare(measurements: &[Measurement], baseline: &[Measurement], threshold: f64) -> Vec<Comparison> {
+    measurements.iter()
+        .filter_map(|measurement| {
+            let baseline = baseline.iter().find(|baseline| baseline.key == measurement.key)?.stats.median;
+            let current = measurement.stats.median;
+            let change = (current.as_nanos() as f64 / baseline.as_nanos().max(1) as f64 - 1.) * 100.;
+            Some(Comparison { key: measurement.key, baseline, current, change, regressed: change > threshold })
+        })
+        .collect()
+}
+
+pub fn format_comparison(comparison: &Comparison) -> String {
+    let SolutionKey { year, day, part } = comparison.key;
+    format!(
+        "{} day {:02} part {}: {:>w$} -> {:>w$} {:>+8.1}%{}",
+        year, day, part,
+        format_duration(comparison.baseline),
+        format_duration(comparison.current),
+        comparison.change,
+        if comparison.regressed { " REGRESSED" } else { "" },
+        w = W_STAT,
+    )
+}
+
+/// The median of each part by day, in the columns of the compact table kept in the README.
+type TableRows = BTreeMap<u8, [String; 3]>;
+
+/// The headings of the columns in `TableRows`, after the day.
+const TABLE_COLUMNS: [&str; 3] = ["parse", "part 1", "part 2"];
+
+fn format_table(rows: &TableRows) -> String {
+    let [parse, part_1, part_2] = TABLE_COLUMNS;
+    let mut result = format!("{:<w$}{:<p$}{:<p$}{}\n", "day", parse, part_1, part_2, w = W_DAY + 2, p = W_STAT);
+    writeln!(result, "{:-<w$}", "", w = W_DAY + 2 + W_STAT * 3).unwrap();
+    for (day, cells) in rows {
+        let mut row = format!("{:<w$}", format!("day {:02}", day), w = W_DAY + 2);
+        for cell in cells {
+            write!(row, "{:<w$}", cell, w = W_STAT).unwrap();
+        }
+        writeln!(result, "{}", row.trim_end()).unwrap();
+    }
+    result
 }
 
-pub fn find_input(day: &str) -> InputData {
-    let file_name = format!("input/year2021/{}", day);
-    let regular_location = Path::new(file_name.as_str());
-    if regular_location.exists() {
-        InputData::from_file(regular_location.to_str().unwrap())
-    } else {
-        let file_name = format!("inputs/{}.in", &day[3..]);
-        InputData::from_file(file_name.as_str())
+/// Reads back the lines of a table, matching cells to columns by the headings in its first line, so
+/// that tables from before a column was added still end up in the right place.
+fn parse_table(lines: &[&str]) -> TableRows {
+    let Some((header, lines)) = lines.split_first() else {
+        return TableRows::new();
+    };
+    let columns = TABLE_COLUMNS.iter().enumerate()
+        .filter_map(|(index, heading)| Some((header.find(heading)?, index)))
+        .sorted()
+        .collect_vec();
+    lines.iter()
+        .filter_map(|line| {
+            let chars = line.chars().collect_vec();
+            let cell = |start: usize, end: usize| {
+                let cell = chars[start.min(chars.len())..end.min(chars.len())].iter().collect::<String>();
+                cell.trim().to_owned()
+            };
+            let day = cell(0, columns.first().map_or(chars.len(), |&(start, _)| start)).strip_prefix("day ")?.parse().ok()?;
+            let mut cells = ["-", "-", "-"].map(str::to_owned);
+            for (i, &(start, index)) in columns.iter().enumerate() {
+                let value = cell(start, columns.get(i + 1).map_or(chars.len(), |&(end, _)| end));
+                if !value.is_empty() {
+                    cells[index] = value;
+                }
+            }
+            Some((day, cells))
+        })
+        .collect()
+}
+
+/// Puts the medians of `measurements` into the table under the `## <year>` heading, adding the
+/// section if missing. Days and parts that were not measured keep what the table said before.
+pub fn update_readme(readme: &str, year: u16, measurements: &[Measurement]) -> String {
+    let heading = format!("## {}", year);
+    let lines = readme.lines().collect::<Vec<_>>();
+    let block = lines.iter().position(|line| *line == heading).and_then(|heading_line| {
+        let start = heading_line + 1 + lines[heading_line + 1..].iter().position(|line| line.starts_with("```"))?;
+        let end = start + 1 + lines[start + 1..].iter().position(|line| line.starts_with("```"))?;
+        Some((start, end))
+    });
+    let mut rows = block
+        .map(|(start, end)| parse_table(&lines[start + 1..end]))
+        .unwrap_or_else(TableRows::new);
+    for measurement in measurements {
+        let cells = rows.entry(measurement.key.day).or_insert_with(|| ["-", "-", "-"].map(str::to_owned));
+        let column = [PARSE_PART, 1, 2].iter().position(|&part| part == measurement.key.part).unwrap();
+        cells[column] = format_duration(measurement.stats.median);
+    }
+    let table = format_table(&rows);
+    match block {
+        Some((start, end)) => {
+            let mut result = lines[..=start].join("\n") + "\n";
+            result.push_str(&table);
+            result.push_str(&lines[end..].join("\n"));
+            result.push('\n');
+            result
+        }
+        None => format!("{}\n{}\n\n```\n{}```\n", readme.trim_end_matches('\n').to_owned() + "\n", heading, table),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::unindent;
+
+    use super::*;
+
+    #[test]
+    fn stats_are_computed() {
+        let samples = [9, 2, 5, 4, 4, 5, 7, 4].iter().map(|&millis| Duration::from_millis(millis)).collect();
+
+        let stats = Stats::from_samples(samples);
+
+        assert_eq!(stats, Stats {
+            iterations: 8,
+            min: Duration::from_millis(2),
+            median: Duration::from_nanos(4_500_000),
+            mean: Duration::from_millis(5),
+            stddev: Duration::from_nanos(2_138_090),
+            p95: Duration::from_millis(9),
+        });
+    }
+
+    #[test]
+    fn single_sample_has_no_deviation() {
+        let stats = Stats::from_samples(vec![Duration::from_micros(3)]);
+
+        assert_eq!(stats.median, Duration::from_micros(3));
+        assert_eq!(stats.p95, Duration::from_micros(3));
+        assert_eq!(stats.stddev, Duration::ZERO);
+    }
+
+    #[test]
+    fn csv_can_be_parsed_back() {
+        let mut measurements = vec![measurement(1, 1, 1_500), measurement(1, 2, 2_000_000)];
+        measurements[1].alloc = Some(AllocStats { allocated: 4096, allocations: 3, peak: 2048 });
+
+        let csv = to_csv(&measurements);
+
+        assert_eq!(parse_csv(&csv), Ok(measurements));
+    }
+
+    #[test]
+    fn csv_without_allocation_columns_can_be_parsed() {
+        let result = parse_csv("year,day,part,iterations,min_ns,median_ns,mean_ns,stddev_ns,p95_ns\n2021,1,1,10,1500,1500,1500,0,1500\n");
+
+        assert_eq!(result, Ok(vec![measurement(1, 1, 1_500)]));
+    }
+
+    #[test]
+    fn json_lists_all_measurements() {
+        let mut measurements = vec![measurement(1, 1, 1_500), measurement(1, 2, 2_000_000)];
+        measurements[1].alloc = Some(AllocStats { allocated: 4096, allocations: 3, peak: 2048 });
+
+        let json = to_json(&measurements);
+
+        assert_eq!(json, unindent(r#"
+            [
+              {"year": 2021, "day": 1, "part": 1, "iterations": 10, "min_ns": 1500, "median_ns": 1500, "mean_ns": 1500, "stddev_ns": 0, "p95_ns": 1500},
+              {"year": 2021, "day": 1, "part": 2, "iterations": 10, "min_ns": 2000000, "median_ns": 2000000, "mean_ns": 2000000, "stddev_ns": 0, "p95_ns": 2000000, "allocated_bytes": 4096, "allocations": 3, "peak_bytes": 2048}
+            ]
+        "#));
+    }
+
+    #[test]
+    fn medians_slower_beyond_threshold_are_regressions() {
+        let baseline = vec![measurement(1, 1, 1_000), measurement(1, 2, 1_000), measurement(2, 1, 1_000)];
+        let current = vec![measurement(1, 1, 1_050), measurement(1, 2, 1_200), measurement(3, 1, 5_000)];
+
+        let result = compare(&current, &baseline, 10.);
+
+        assert_eq!(result.iter().map(|comparison| (comparison.key.day, comparison.key.part, comparison.regressed)).collect::<Vec<_>>(), vec![(1, 1, false), (1, 2, true)]);
+        assert!((result[1].change - 20.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn readme_table_is_replaced() {
+        let readme = unindent("
+            Intro
+
+            ## 2021
+
+            ```
+            old table
+            ```
+
+            Outro
+        ");
+
+        let measurements = [measurement(1, 1, 1_500), measurement(1, 2, 2_000_000), measurement(2, 0, 800), measurement(2, 1, 200), measurement(2, 2, 300)];
+
+        let result = update_readme(&readme, 2021, &measurements);
+
+        assert_eq!(result, unindent("
+            Intro
+
+            ## 2021
+
+            ```
+            day       parse     part 1    part 2
+            ----------------------------------------
+            day 01    -         1.50 µs   2.00 ms
+            day 02    800 ns    200 ns    300 ns
+            ```
+
+            Outro
+        "));
+    }
+
+    #[test]
+    fn readme_table_from_before_parse_column_is_kept() {
+        let readme = unindent("
+            ## 2021
+
+            ```
+            day       part 1    part 2
+            ------------------------------
+            day 01    0.009 ms  0.009 ms
+            day 02    0.007 ms  0.007 ms
+            day 03    0.032 ms  0.013 ms
+            day 04    0.066 ms  0.067 ms
+            day 05    2.880 ms  5.275 ms
+            day 06    0.001 ms  0.001 ms
+            day 07    0.010 ms  0.015 ms
+            day 08    0.024 ms  0.038 ms
+            day 09    0.067 ms  0.028 ms
+            day 10    0.009 ms  0.015 ms
+            day 11    0.026 ms  0.149 ms
+            day 12    0.005 ms  0.064 ms
+            day 13    0.024 ms  0.020 ms
+            day 14    0.007 ms  0.013 ms
+            day 15    0.497 ms  14.50 ms
+            day 16    0.002 ms  0.002 ms
+            day 17    0.000 ms  0.002 ms
+            day 18    0.097 ms  3.169 ms
+            day 19    5.821 ms  5.789 ms
+            day 20    0.030 ms  1.494 ms
+            day 21    0.001 ms  0.292 ms
+            day 22    0.578 ms  27.72 ms
+            day 23    10.33 ms  24.53 ms
+            day 24    0.007 ms  0.007 ms
+            day 25    18.13 ms  0.000 ms
+            ```
+        ");
+
+        let result = update_readme(&readme, 2021, &[measurement(5, 1, 3_000_000)]);
+
+        let lines = result.lines().collect_vec();
+        assert_eq!(lines[3], "day       parse     part 1    part 2");
+        assert_eq!(lines[5], "day 01    -         0.009 ms  0.009 ms");
+        assert_eq!(lines[9], "day 05    -         3.00 ms   5.275 ms");
+        assert_eq!(lines[29], "day 25    -         18.13 ms  0.000 ms");
+        assert_eq!(lines.len(), 31);
+    }
+
+    #[test]
+    fn readme_section_is_added_for_new_year() {
+        let result = update_readme("Intro\n", 2022, &[measurement(3, 1, 1_000)]);
+
+        assert_eq!(result, unindent("
+            Intro
+
+            ## 2022
+
+            ```
+            day       parse     part 1    part 2
+            ----------------------------------------
+            day 03    -         1.00 µs   -
+            ```
+        "));
+    }
+
+    #[test]
+    fn readme_table_keeps_rows_that_were_not_measured() {
+        let readme = unindent("
+            ## 2021
+
+            ```
+            day       parse     part 1    part 2
+            ----------------------------------------
+            day 01    -         1.50 µs   2.00 ms
+            day 02    800 ns    200 ns    300 ns
+            day 04    -         45.6 ms   123 ms
+            ```
+        ");
+
+        let result = update_readme(&readme, 2021, &[measurement(2, 1, 250), measurement(3, 2, 5_000)]);
+
+        assert_eq!(result, unindent("
+            ## 2021
+
+            ```
+            day       parse     part 1    part 2
+            ----------------------------------------
+            day 01    -         1.50 µs   2.00 ms
+            day 02    800 ns    250 ns    300 ns
+            day 03    -         -         5.00 µs
+            day 04    -         45.6 ms   123 ms
+            ```
+        "));
+    }
+
+    fn measurement(day: u8, part: u8, nanos: u64) -> Measurement {
+        let duration = Duration::from_nanos(nanos);
+        Measurement {
+            key: SolutionKey { year: 2021, day, part },
+            stats: Stats { iterations: 10, min: duration, median: duration, mean: duration, stddev: Duration::ZERO, p95: duration },
+            alloc: None,
+        }
+    }
+
+    #[test]
+    fn durations_are_formatted_in_fitting_unit() {
+        assert_eq!(format_duration(Duration::from_nanos(7)), "7 ns");
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999 ns");
+        assert_eq!(format_duration(Duration::from_nanos(1_234)), "1.23 µs");
+        assert_eq!(format_duration(Duration::from_nanos(999_999)), "1.00 ms");
+        assert_eq!(format_duration(Duration::from_micros(45_600)), "45.6 ms");
+        assert_eq!(format_duration(Duration::from_micros(123_400)), "123 ms");
+        assert_eq!(format_duration(Duration::from_millis(2_500)), "2.50 s");
+        assert_eq!(format_duration(Duration::from_secs(150)), "150 s");
+        assert_eq!(format_duration(Duration::from_secs(15_000)), "15000 s");
+    }
+
+    #[test]
+    fn bytes_are_formatted_in_fitting_unit() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(999), "999 B");
+        assert_eq!(format_bytes(4_096), "4.10 kB");
+        assert_eq!(format_bytes(12_500_000), "12.5 MB");
     }
 }
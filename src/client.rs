@@ -0,0 +1,303 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use ureq::Agent;
+
+use crate::answer::Answer;
+use crate::config::Config;
+use crate::resolver::InputResolver;
+use crate::submissions::Verdict;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const BASE_URL_VARIABLE: &str = "AOC_BASE_URL";
+pub const SESSION_VARIABLE: &str = "AOC_SESSION";
+pub const SESSION_FILE_VARIABLE: &str = "AOC_SESSION_FILE";
+pub const DEFAULT_SESSION_FILE: &str = ".session";
+
+const USER_AGENT: &str = "github.com/kostislav/advent-of-rust";
+
+/// Talks to the puzzle site, authenticated by the session cookie of a logged in user.
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        let agent = Agent::config_builder()
+            .http_status_as_error(false)
+            .user_agent(USER_AGENT)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.trim().to_owned(),
+            agent,
+        }
+    }
+
+    /// The base URL comes from `AOC_BASE_URL` or the `base_url` setting. The session token comes from
+    /// `AOC_SESSION`, or from the file named by `AOC_SESSION_FILE` or the `session_file` setting.
+    pub fn from_config(config: &Config) -> Result<Self, ClientError> {
+        let base_url = config.get(BASE_URL_VARIABLE, "base_url").unwrap_or_else(|| DEFAULT_BASE_URL.to_owned());
+        let session = match config.get(SESSION_VARIABLE, "session") {
+            Some(session) => session,
+            None => {
+                let session_file = config.get(SESSION_FILE_VARIABLE, "session_file").unwrap_or_else(|| DEFAULT_SESSION_FILE.to_owned());
+                fs::read_to_string(&session_file).map_err(|error| ClientError::NoSession { session_file, error })?
+            }
+        };
+        Ok(Self::new(&base_url, &session))
+    }
+
+    pub fn fetch_input(&self, year: u16, day: u8) -> Result<Vec<u8>, ClientError> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        let mut response = self.agent.get(&url)
+            .header("Cookie", &format!("session={}", self.session))
+            .call()
+            .map_err(ClientError::Transport)?;
+        let status = response.status().as_u16();
+        let body = response.body_mut().read_to_vec().map_err(ClientError::Transport)?;
+        if status == 200 {
+            Ok(body)
+        } else {
+            Err(ClientError::Status { status, message: String::from_utf8_lossy(&body).trim().to_owned() })
+        }
+    }
+
+    /// Submits `answer` and returns the verdict together with the seconds to wait before the next attempt.
+    pub fn submit_answer(&self, year: u16, day: u8, part: u8, answer: &Answer) -> Result<(Verdict, u64), ClientError> {
+        let url = format!("{}/{}/day/{}/answer", self.base_url, year, day);
+        let mut response = self.agent.post(&url)
+            .header("Cookie", &format!("session={}", self.session))
+            .send_form([("level", part.to_string()), ("answer", answer.to_string())])
+            .map_err(ClientError::Transport)?;
+        let status = response.status().as_u16();
+        let body = response.body_mut().read_to_string().map_err(ClientError::Transport)?;
+        if status != 200 {
+            return Err(ClientError::Status { status, message: body.trim().to_owned() });
+        }
+        let verdict = parse_verdict(&body).ok_or_else(|| ClientError::UnknownResponse(body.clone()))?;
+        Ok((verdict, parse_retry_wait(&body).unwrap_or(0)))
+    }
+}
+
+fn parse_verdict(page: &str) -> Option<Verdict> {
+    Some(if page.contains("That's the right answer") {
+        Verdict::Right
+    } else if page.contains("your answer is too high") {
+        Verdict::TooHigh
+    } else if page.contains("your answer is too low") {
+        Verdict::TooLow
+    } else if page.contains("That's not the right answer") {
+        Verdict::Wrong
+    } else if page.contains("You gave an answer too recently") {
+        Verdict::Wait(parse_wait(page).unwrap_or(60))
+    } else if page.contains("Did you already complete it?") {
+        Verdict::AlreadySolved
+    } else {
+        return None;
+    })
+}
+
+/// Parses the `Please wait one minute before trying again` part of a rejection.
+fn parse_retry_wait(page: &str) -> Option<u64> {
+    let (_, rest) = page.split_once("lease wait ")?;
+    let (duration, _) = rest.split_once(" before trying again")?;
+    let (amount, unit) = duration.split_once(' ')?;
+    let amount = match amount {
+        "one" | "a" | "an" => 1,
+        _ => amount.parse().unwrap_or(1),
+    };
+    let multiplier = match unit.trim_end_matches('s') {
+        "hour" => 3600,
+        "minute" => 60,
+        "second" => 1,
+        _ => 60,
+    };
+    Some(amount * multiplier)
+}
+
+/// Parses the `You have 1m 30s left to wait` part of the rate limit message.
+fn parse_wait(page: &str) -> Option<u64> {
+    let (_, rest) = page.split_once("You have ")?;
+    let (duration, _) = rest.split_once(" left to wait")?;
+    duration.split_whitespace()
+        .map(|component| {
+            let split = component.len() - 1;
+            let multiplier = match &component[split..] {
+                "h" => 3600,
+                "m" => 60,
+                "s" => 1,
+                _ => return None,
+            };
+            component[..split].parse::<u64>().ok().map(|amount| amount * multiplier)
+        })
+        .sum()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fetched {
+    Cached(String),
+    Downloaded(String),
+}
+
+/// Downloads the input of a day to the first search path of `resolver`, unless it is already present
+/// in one of them.
+pub fn fetch_input(client: &Client, resolver: &InputResolver, year: u16, day: u8) -> Result<Fetched, ClientError> {
+    if let Some(cached) = resolver.cached_location(year, day) {
+        return Ok(Fetched::Cached(cached));
+    }
+    let location = resolver.download_location(year, day).ok_or(ClientError::NowhereToStore)?;
+    let input = client.fetch_input(year, day)?;
+    if let Some(directory) = Path::new(&location).parent() {
+        fs::create_dir_all(directory).map_err(ClientError::Io)?;
+    }
+    fs::write(&location, input).map_err(ClientError::Io)?;
+    Ok(Fetched::Downloaded(location))
+}
+
+#[derive(Debug)]
+pub enum ClientError {
+    NoSession { session_file: String, error: io::Error },
+    NowhereToStore,
+    Status { status: u16, message: String },
+    UnknownResponse(String),
+    Transport(ureq::Error),
+    Io(io::Error),
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::NoSession { session_file, error } => write!(f, "No session token in {} or {}: {}", SESSION_VARIABLE, session_file, error),
+            ClientError::NowhereToStore => write!(f, "None of the input search paths is a file"),
+            ClientError::Status { status: 404, .. } => write!(f, "The puzzle is not available yet"),
+            ClientError::Status { status, message } => write!(f, "Request failed with status {}: {}", status, message),
+            ClientError::UnknownResponse(page) => write!(f, "Could not understand the response: {}", page.trim()),
+            ClientError::Transport(error) => write!(f, "Request failed: {}", error),
+            ClientError::Io(error) => write!(f, "Could not store the input: {}", error),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+
+    use crate::test_support::{TempDirectory, TestServer};
+
+    use super::*;
+
+    #[test]
+    fn input_is_downloaded_with_session_cookie() {
+        let server = TestServer::respond_once(200, "1\n2\n3\n");
+        let client = Client::new(&server.base_url, "abc123\n");
+
+        let result = client.fetch_input(2021, 7).unwrap();
+
+        let request = server.request();
+        assert_eq!(result, b"1\n2\n3\n");
+        assert!(request.starts_with("GET /2021/day/7/input HTTP/1.1\r\n"));
+        assert!(request.to_lowercase().contains("cookie: session=abc123\r\n"));
+    }
+
+    #[test]
+    fn error_status_is_reported() {
+        let server = TestServer::respond_once(400, "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n");
+        let client = Client::new(&server.base_url, "expired");
+
+        let result = client.fetch_input(2021, 7).err().unwrap();
+
+        assert_eq!(result.to_string(), "Request failed with status 400: Puzzle inputs differ by user.  Please log in to get your puzzle input.");
+    }
+
+    #[test]
+    fn answer_is_submitted_as_form() {
+        let server = TestServer::respond_once(200, "<article><p>That's the right answer!  You are one gold star closer.</p></article>");
+        let client = Client::new(&server.base_url, "abc123");
+
+        let result = client.submit_answer(2021, 7, 2, &Answer::from("a b&c")).unwrap();
+
+        let request = server.request();
+        assert_eq!(result, (Verdict::Right, 0));
+        assert!(request.starts_with("POST /2021/day/7/answer HTTP/1.1\r\n"));
+        assert!(request.to_lowercase().contains("cookie: session=abc123\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=2&answer=a+b%26c"));
+    }
+
+    #[test]
+    fn responses_are_parsed() {
+        assert_eq!(
+            parse_verdict("<p>That's not the right answer; your answer is too high.  If you're stuck, ...</p>"),
+            Some(Verdict::TooHigh),
+        );
+        assert_eq!(
+            parse_verdict("<p>That's not the right answer; your answer is too low.</p>"),
+            Some(Verdict::TooLow),
+        );
+        assert_eq!(
+            parse_verdict("<p>That's not the right answer.  If you're stuck, make sure you're using the full input data</p>"),
+            Some(Verdict::Wrong),
+        );
+        assert_eq!(
+            parse_verdict("<p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 30s left to wait.</p>"),
+            Some(Verdict::Wait(90)),
+        );
+        assert_eq!(
+            parse_verdict("<p>You don't seem to be solving the right level.  Did you already complete it?</p>"),
+            Some(Verdict::AlreadySolved),
+        );
+        assert_eq!(parse_verdict("<p>Something else</p>"), None);
+    }
+
+    #[test]
+    fn waits_after_rejections_are_parsed() {
+        assert_eq!(
+            parse_retry_wait("<p>That's not the right answer.  Please wait one minute before trying again.</p>"),
+            Some(60),
+        );
+        assert_eq!(
+            parse_retry_wait("<p>Because you have guessed incorrectly 4 times on this puzzle, please wait 5 minutes before trying again.</p>"),
+            Some(300),
+        );
+        assert_eq!(parse_retry_wait("<p>That's the right answer!</p>"), None);
+    }
+
+    #[test]
+    fn downloaded_input_is_stored_in_first_search_path() {
+        let directory = TempDirectory::new("client_stored");
+        let server = TestServer::respond_once(200, "forward 5\n");
+        let client = Client::new(&server.base_url, "abc123");
+        let resolver = InputResolver::new(vec![format!("{}/year{{year}}/day{{day}}", directory.display())], None);
+
+        let result = fetch_input(&client, &resolver, 2021, 2).unwrap();
+
+        let location = format!("{}/year2021/day02", directory.display());
+        assert_eq!(result, Fetched::Downloaded(location.clone()));
+        assert_eq!(fs::read(location).unwrap(), b"forward 5\n");
+    }
+
+    #[test]
+    fn cached_input_is_not_downloaded_again() {
+        let directory = TempDirectory::new("client_cached");
+        fs::write(directory.join("shared-02"), "forward 5\n").unwrap();
+        let client = Client::new("http://127.0.0.1:9", "abc123");
+        let resolver = InputResolver::new(
+            vec![
+                format!("{}/mine-{{day}}", directory.display()),
+                format!("{}/shared-{{day}}", directory.display()),
+            ],
+            None,
+        );
+
+        let result = fetch_input(&client, &resolver, 2021, 2).unwrap();
+
+        assert_eq!(result, Fetched::Cached(format!("{}/shared-02", directory.display())));
+    }
+}
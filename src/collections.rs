@@ -38,6 +38,12 @@ impl<V: Default + Copy + Eq> U8Map<V> {
     }
 }
 
+impl<V: Default + Copy + Eq> Default for U8Map<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<V: Default + Copy + Eq> FromIterator<(u8, V)> for U8Map<V> {
     fn from_iter<T: IntoIterator<Item=(u8, V)>>(iter: T) -> Self {
         let mut result = Self::new();
@@ -56,11 +62,12 @@ impl U8Map<usize> {
 
 #[macro_export]
 macro_rules! u8_map {
-    ($($key:expr => $value:expr,)+) => { u8_map!($($key => $value),+) };
+    ($($key:expr => $value:expr,)+) => { $crate::u8_map!($($key => $value),+) };
     ($($key:expr => $value:expr),*) => {
         {
             let mut _map = $crate::collections::U8Map::new();
             $(
+                #[allow(clippy::char_lit_as_u8)]
                 let _ = _map.insert($key as u8, $value);
             )*
             _map
@@ -0,0 +1,192 @@
+//! Small parsers over a `ParseStream`, for inputs that don't fit a `#[pattern]`. A parser is any
+//! closure that takes the stream and returns a result. A failing parser may leave the stream
+//! anywhere, but `alt`, `many0`, `many1`, `sep_by` and `opt` rewind it before trying something else.
+
+use crate::input::{ParseError, ParseStream, ParseYolo};
+
+pub trait Parser<'a, T>: Fn(&mut ParseStream<'a>) -> Result<T, ParseError<'a>> {}
+
+impl<'a, T, F: Fn(&mut ParseStream<'a>) -> Result<T, ParseError<'a>>> Parser<'a, T> for F {}
+
+/// Any `ParseYolo` type as a parser.
+pub fn parse<'a, T: ParseYolo<'a>>() -> impl Parser<'a, T> {
+    |stream| stream.parse_yolo()
+}
+
+pub fn literal<'a>(text: &str) -> impl Parser<'a, ()> + '_ {
+    move |stream| stream.expect(text)
+}
+
+/// Tries each parser of a tuple in turn. If all fail, the error that got the furthest is returned.
+pub fn alt<'a, T, A: Alt<'a, T>>(parsers: A) -> impl Parser<'a, T> {
+    move |stream| parsers.parse_first(stream)
+}
+
+pub trait Alt<'a, T> {
+    fn parse_first(&self, stream: &mut ParseStream<'a>) -> Result<T, ParseError<'a>>;
+}
+
+macro_rules! alt_tuples {
+    ($(($($parser:ident),+)),*) => {
+        $(
+            #[allow(non_snake_case)]
+            impl<'a, T, $($parser: Parser<'a, T>),+> Alt<'a, T> for ($($parser,)+) {
+                fn parse_first(&self, stream: &mut ParseStream<'a>) -> Result<T, ParseError<'a>> {
+                    let ($($parser,)+) = self;
+                    let mut furthest: Option<ParseError<'a>> = None;
+                    $(
+                        match stream.try_parse($parser) {
+                            Ok(result) => return Ok(result),
+                            Err(error) => if furthest.as_ref().is_none_or(|furthest| error.offset() > furthest.offset()) {
+                                furthest = Some(error);
+                            },
+                        }
+                    )+
+                    Err(furthest.unwrap())
+                }
+            }
+        )*
+    };
+}
+
+alt_tuples!((A, B), (A, B, C), (A, B, C, D), (A, B, C, D, E), (A, B, C, D, E, F));
+
+/// As many matches as there are, possibly none. Stops after a match that consumed nothing.
+pub fn many0<'a, T, P: Parser<'a, T>>(parser: P) -> impl Parser<'a, Vec<T>> {
+    move |stream| {
+        let mut results = Vec::new();
+        repeat(stream, &parser, &mut results);
+        Ok(results)
+    }
+}
+
+pub fn many1<'a, T, P: Parser<'a, T>>(parser: P) -> impl Parser<'a, Vec<T>> {
+    move |stream| {
+        let mut results = vec![stream.try_parse(&parser)?];
+        repeat(stream, &parser, &mut results);
+        Ok(results)
+    }
+}
+
+/// Items separated by `separator`, possibly none. A trailing separator is left in the stream.
+pub fn sep_by<'a, T, S, P: Parser<'a, T>, SP: Parser<'a, S>>(parser: P, separator: SP) -> impl Parser<'a, Vec<T>> {
+    move |stream| {
+        let mut results = Vec::new();
+        if let Ok(first) = stream.try_parse(&parser) {
+            results.push(first);
+            repeat(stream, |stream: &mut ParseStream<'a>| {
+                separator(stream)?;
+                parser(stream)
+            }, &mut results);
+        }
+        Ok(results)
+    }
+}
+
+pub fn opt<'a, T, P: Parser<'a, T>>(parser: P) -> impl Parser<'a, Option<T>> {
+    move |stream| Ok(stream.try_parse(&parser).ok())
+}
+
+pub fn delimited<'a, O, T, C, OP: Parser<'a, O>, P: Parser<'a, T>, CP: Parser<'a, C>>(open: OP, parser: P, close: CP) -> impl Parser<'a, T> {
+    move |stream| {
+        open(stream)?;
+        let result = parser(stream)?;
+        close(stream)?;
+        Ok(result)
+    }
+}
+
+pub fn terminated<'a, T, E, P: Parser<'a, T>, EP: Parser<'a, E>>(parser: P, end: EP) -> impl Parser<'a, T> {
+    move |stream| {
+        let result = parser(stream)?;
+        end(stream)?;
+        Ok(result)
+    }
+}
+
+/// The bytes the parser consumed, instead of its result.
+pub fn recognize<'a, T, P: Parser<'a, T>>(parser: P) -> impl Parser<'a, &'a [u8]> {
+    move |stream| {
+        let start = stream.position();
+        parser(stream)?;
+        Ok(stream.slice_since(start))
+    }
+}
+
+fn repeat<'a, T, P: Parser<'a, T>>(stream: &mut ParseStream<'a>, parser: P, results: &mut Vec<T>) {
+    loop {
+        let start = stream.position();
+        match stream.try_parse(&parser) {
+            Ok(result) => results.push(result),
+            Err(_) => break,
+        }
+        if stream.position() == start {
+            break;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::input::{Expected, Ident};
+
+    use super::*;
+
+    #[test]
+    fn alt_takes_the_first_match() {
+        let parser = alt((recognize(literal("on")), recognize(literal("off")), recognize(parse::<u8>())));
+
+        assert_eq!(parser(&mut ParseStream::new(b"off")), Ok(b"off".as_slice()));
+        assert_eq!(parser(&mut ParseStream::new(b"42")), Ok(b"42".as_slice()));
+    }
+
+    #[test]
+    fn alt_reports_the_furthest_error() {
+        let parser = alt((literal("abc"), terminated(literal("ab"), literal("d"))));
+
+        let error = parser(&mut ParseStream::new(b"abx")).unwrap_err();
+
+        assert_eq!((error.offset(), error.expected()), (2, &Expected::Literal("d".to_owned())));
+    }
+
+    #[test]
+    fn repetition_works() {
+        let characters = many1(parse::<char>());
+        let mut stream = ParseStream::new(b"");
+
+        assert_eq!(many0(literal("ab"))(&mut ParseStream::new(b"ababa")).map(|results| results.len()), Ok(2));
+        assert_eq!(characters(&mut stream).unwrap_err().expected(), &Expected::AnyByte);
+        assert_eq!(stream.position(), 0);
+    }
+
+    #[test]
+    fn sep_by_leaves_a_trailing_separator() {
+        let mut stream = ParseStream::new(b"[1, 2, 3], ");
+        let list = delimited(literal("["), sep_by(parse::<u8>(), literal(", ")), literal("]"));
+
+        assert_eq!(list(&mut stream), Ok(vec![1, 2, 3]));
+        assert_eq!(opt(parse::<u8>())(&mut stream), Ok(None));
+        assert!(stream.try_consume(", "));
+    }
+
+    #[test]
+    fn combinators_work_in_parse_yolo_impls() {
+        struct Call<'a> {
+            name: Ident<'a>,
+            arguments: Vec<i32>,
+        }
+
+        impl<'a> ParseYolo<'a> for Call<'a> {
+            fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> {
+                let name = stream.parse_yolo()?;
+                let arguments = delimited(literal("("), sep_by(parse(), literal(",")), literal(")"))(stream)?;
+                Ok(Self { name, arguments })
+            }
+        }
+
+        let call = ParseStream::new(b"max(3,-4)").parse_yolo::<Call>().unwrap();
+
+        assert_eq!((call.name.as_str(), call.arguments), ("max", vec![3, -4]));
+    }
+}
@@ -0,0 +1,75 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+
+pub const CONFIG_FILE: &str = "aoc.conf";
+
+/// Settings from `aoc.conf`, one `key = value` per line. Keys may repeat, `#` starts a comment line.
+/// Environment variables take precedence over the file.
+#[derive(Default)]
+pub struct Config {
+    values: Vec<(String, String)>,
+}
+
+impl Config {
+    pub fn load(path: &str) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Self::parse(&contents)
+                .map_err(|line| io::Error::new(ErrorKind::InvalidData, format!("Malformed line in {}: {}", path, line))),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, &str> {
+        let mut values = Vec::new();
+        for line in contents.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let (key, value) = line.split_once('=').ok_or(line)?;
+            values.push((key.trim().to_owned(), value.trim().to_owned()));
+        }
+        Ok(Self { values })
+    }
+
+    /// The last value of `key`, unless the environment variable `env_var` is set.
+    pub fn get(&self, env_var: &str, key: &str) -> Option<String> {
+        env::var(env_var).ok()
+            .or_else(|| self.get_all(key).last().map(str::to_owned))
+    }
+
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item=&'a str> + 'a {
+        self.values.iter()
+            .filter(move |(existing_key, _)| existing_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn values_are_parsed() {
+        let config = Config::parse("
+            # where the inputs are
+            input_path = input/{user}/year{year}/day{day}
+            input_path = input/year{year}/day{day}
+
+            user=alice
+        ").unwrap();
+
+        assert_eq!(config.get_all("input_path").collect_vec(), vec!["input/{user}/year{year}/day{day}", "input/year{year}/day{day}"]);
+        assert_eq!(config.get("AOC_TEST_UNSET_VARIABLE", "user"), Some("alice".to_owned()));
+        assert_eq!(config.get("AOC_TEST_UNSET_VARIABLE", "session_file"), None);
+    }
+
+    #[test]
+    fn lines_without_value_are_rejected() {
+        let result = Config::parse("user = me\ninput_path\n");
+
+        assert_eq!(result.err(), Some("input_path"));
+    }
+}
@@ -34,6 +34,12 @@ impl<T: Eq + Hash + Clone> HashIndexer<T> {
     }
 }
 
+impl<T: Eq + Hash + Clone> Default for HashIndexer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 
 pub fn shortest_path<T, I, S, F, TF>(starting_node: T, target_node_predicate: TF, mut dist: S, edge_supplier: F) -> usize
     where T: Eq + Copy,
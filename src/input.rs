@@ -1,15 +1,22 @@
 use std::cmp::Reverse;
 use std::collections::{BinaryHeap, VecDeque};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
 use std::fs;
+use std::io;
 use std::hash::Hash;
-use std::iter::{Peekable, successors};
-use std::ops::{Index, IndexMut};
+use std::iter::Peekable;
+use std::ops::{Index, IndexMut, Range};
+use std::str::FromStr;
 
 use ahash::AHashMap;
 use bstr::ByteSlice;
 use itertools::Itertools;
 use itertools::MinMaxResult::MinMax;
-use num::ToPrimitive;
+use num::{Float, PrimInt, ToPrimitive};
+use regex::bytes::Regex;
+
+use crate::array::Array2d;
 
 pub struct InputData {
     data: Vec<u8>,
@@ -17,7 +24,14 @@ pub struct InputData {
 
 impl InputData {
     pub fn from_file(path: &str) -> Self {
-        let data = fs::read(path).unwrap();
+        Self::try_from_file(path).unwrap()
+    }
+
+    pub fn try_from_file(path: &str) -> io::Result<Self> {
+        Ok(Self { data: fs::read(path)? })
+    }
+
+    pub fn from_bytes(data: Vec<u8>) -> Self {
         Self { data }
     }
 
@@ -29,11 +43,75 @@ impl InputData {
         self.data.lines()
     }
 
-    pub fn lines_as<'a, 'b: 'a, T: ParseYolo<'a>>(&'b self) -> impl Iterator<Item=T> + 'a {
+    pub fn lines_as<'a, 'b: 'a, T: ParseYolo<'a> + 'a>(&'b self) -> impl Iterator<Item=T> + 'a {
+        Section::new(&self.data).lines_as()
+    }
+
+    /// Stops after the first line that fails to parse.
+    pub fn try_lines_as<'a, 'b: 'a, T: ParseYolo<'a> + 'a>(&'b self) -> impl Iterator<Item=Result<T, ParseError<'a>>> + 'a {
+        Section::new(&self.data).try_lines_as()
+    }
+
+    /// The parts of the input between blank lines, without surrounding newlines. Empty ones are skipped.
+    pub fn sections(&self) -> impl Iterator<Item=Section<'_>> {
+        Section::new(&self.data).sections()
+    }
+
+    /// Exactly `N` sections, as in `let [rules, messages] = input.split_sections();`.
+    pub fn split_sections<const N: usize>(&self) -> [Section<'_>; N] {
+        self.try_split_sections().unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_split_sections<const N: usize>(&self) -> Result<[Section<'_>; N], SectionCountError> {
+        let sections = self.sections().collect_vec();
+        let found = sections.len();
+        sections.try_into().map_err(|_| SectionCountError { expected: N, found })
+    }
+
+    pub fn stream(&self) -> ParseStream<'_> {
+        self.data.as_slice().stream()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn raw(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// A borrowed part of an input, with the same ways of reading it.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Section<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Section<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item=&'a [u8]> {
+        self.data.lines()
+    }
+
+    pub fn lines_as<T: ParseYolo<'a> + 'a>(self) -> impl Iterator<Item=T> + 'a {
+        self.try_lines_as().map(Result::unwrap)
+    }
+
+    /// Stops after the first line that fails to parse.
+    pub fn try_lines_as<T: ParseYolo<'a> + 'a>(self) -> impl Iterator<Item=Result<T, ParseError<'a>>> + 'a {
         let mut stream = self.stream();
+        let mut failed = false;
         std::iter::from_fn(move || {
-            if stream.has_next() {
-                let line = stream.parse_yolo().unwrap();
+            if stream.has_next() && !failed {
+                let line = stream.parse_yolo();
+                failed = line.is_err();
                 stream.try_consume("\n");
                 Some(line)
             } else {
@@ -42,23 +120,52 @@ impl InputData {
         })
     }
 
-    pub fn stream(&self) -> ParseStream {
-        self.data.as_slice().stream()
+    pub fn sections(self) -> impl Iterator<Item=Section<'a>> {
+        self.data.split_str("\n\n")
+            .map(|section| section.trim_with(|c| c == '\n'))
+            .filter(|section| !section.is_empty())
+            .map(Section::new)
+    }
+
+    pub fn stream(&self) -> ParseStream<'a> {
+        ParseStream::new(self.data)
+    }
+
+    pub fn grid<T, F: Fn(u8) -> T>(&self, transformation: F) -> Array2d<T> {
+        Array2d::from_transformed_bytes(self.data, transformation)
     }
 
     pub fn len(&self) -> usize {
         self.data.len()
     }
 
-    pub fn raw(&self) -> &[u8] {
-        &self.data
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn raw(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct SectionCountError {
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl Display for SectionCountError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Expected {} sections separated by blank lines, found {}", self.expected, self.found)
     }
 }
 
+impl Error for SectionCountError {}
+
 pub fn unindent(input: &str) -> String {
     let lines = input.lines().collect_vec();
-    if lines.len() == 1 {
-        lines[0].to_owned()
+    if lines.len() <= 1 {
+        lines.concat()
     } else {
         let num_spaces_to_remove = lines[1].chars().take_while(|&c| c == ' ').count();
 
@@ -73,6 +180,96 @@ pub fn unindent(input: &str) -> String {
 }
 
 
+/// What a parser was looking for when it failed.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Expected {
+    Literal(String),
+    Digit,
+    Whitespace,
+    /// A number small enough for the named type.
+    InRange(&'static str),
+    AnyByte,
+    /// The names of the variants of an enum.
+    OneOf(&'static [&'static str]),
+    Regex(&'static str),
+    Described(&'static str),
+}
+
+impl Display for Expected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expected::Literal(literal) => write!(f, "{:?}", literal),
+            Expected::Digit => write!(f, "a digit"),
+            Expected::Whitespace => write!(f, "whitespace"),
+            Expected::InRange(type_name) => write!(f, "a number that fits in {}", type_name),
+            Expected::AnyByte => write!(f, "any character"),
+            Expected::OneOf(names) => write!(f, "one of {}", names.join(", ")),
+            Expected::Regex(regex) => write!(f, "a match of /{}/", regex),
+            Expected::Described(description) => write!(f, "{}", description),
+        }
+    }
+}
+
+/// A failed parse. Keeps the parsed bytes, so that the line, column and snippet are only worked out
+/// when someone looks at them.
+#[derive(Clone, Eq, PartialEq)]
+pub struct ParseError<'a> {
+    input: &'a [u8],
+    offset: usize,
+    expected: Expected,
+}
+
+impl<'a> ParseError<'a> {
+    pub fn new(input: &'a [u8], offset: usize, expected: Expected) -> Self {
+        Self { input, offset, expected }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn expected(&self) -> &Expected {
+        &self.expected
+    }
+
+    pub fn line(&self) -> usize {
+        self.input[..self.offset].find_iter("\n").count() + 1
+    }
+
+    pub fn column(&self) -> usize {
+        self.offset - self.line_start() + 1
+    }
+
+    /// The line the error is on.
+    pub fn snippet(&self) -> String {
+        let start = self.line_start();
+        let end = self.input[start..].find_byte(b'\n').map_or(self.input.len(), |length| start + length);
+        String::from_utf8_lossy(&self.input[start..end]).into_owned()
+    }
+
+    fn line_start(&self) -> usize {
+        self.input[..self.offset].rfind_byte(b'\n').map_or(0, |newline| newline + 1)
+    }
+}
+
+impl Display for ParseError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "expected {} at line {}, column {}", self.expected, self.line(), self.column())?;
+        writeln!(f, "{}", self.snippet())?;
+        write!(f, "{:>1$}", "^", self.column())
+    }
+}
+
+/// Same as `Display`, so that an unwrapped error shows where it happened instead of the whole input.
+impl Debug for ParseError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Error for ParseError<'_> {}
+
+
 pub struct ParseStream<'a> {
     bytes: &'a [u8],
     position: usize,
@@ -83,11 +280,11 @@ impl<'a> ParseStream<'a> {
         Self { bytes, position: 0 }
     }
 
-    pub fn parse_yolo<T: ParseYolo<'a>>(&mut self) -> Result<T, ()> {
+    pub fn parse_yolo<T: ParseYolo<'a>>(&mut self) -> Result<T, ParseError<'a>> {
         self.try_parse(T::parse_from_stream)
     }
 
-    pub fn parse_separated<T: ParseSeparated<'a>>(&mut self, separator: &str) -> Result<T, ()> {
+    pub fn parse_separated<T: ParseSeparated<'a>>(&mut self, separator: &str) -> Result<T, ParseError<'a>> {
         self.try_parse(|stream| T::parse_from_stream(stream, separator))
     }
 
@@ -105,7 +302,7 @@ impl<'a> ParseStream<'a> {
         }
     }
 
-    pub fn try_parse<T, F: Fn(&mut ParseStream<'a>) -> Result<T, ()>>(&mut self, parser: F) -> Result<T, ()> {
+    pub fn try_parse<T, F: Fn(&mut ParseStream<'a>) -> Result<T, ParseError<'a>>>(&mut self, parser: F) -> Result<T, ParseError<'a>> {
         let snapshot = self.position;
         let result = parser(self);
         if result.is_err() {
@@ -114,15 +311,21 @@ impl<'a> ParseStream<'a> {
         result
     }
 
-    pub fn expect(&mut self, pattern: &str) -> Result<(), ()> {
+    pub fn expect(&mut self, pattern: &str) -> Result<(), ParseError<'a>> {
         if self.try_consume(pattern) {
             Ok(())
         } else {
-            Err(())
+            Err(self.error(Expected::Literal(pattern.to_owned())))
         }
     }
 
-    pub fn fold_while<T, P: Fn(u8) -> bool, F: Fn(T, u8) -> T>(&mut self, initial: T, predicate: P, f: F) -> Result<T, ()> {
+    /// An error at the current position.
+    pub fn error(&self, expected: Expected) -> ParseError<'a> {
+        ParseError::new(self.bytes, self.position, expected)
+    }
+
+    /// Fails with `expected` unless at least one byte matches `predicate`.
+    pub fn fold_while<T, P: Fn(u8) -> bool, F: Fn(T, u8) -> T>(&mut self, expected: Expected, initial: T, predicate: P, f: F) -> Result<T, ParseError<'a>> {
         let mut acc: T = initial;
         let orig_position = self.position;
         while self.has_next() {
@@ -135,7 +338,7 @@ impl<'a> ParseStream<'a> {
             }
         }
         if self.position == orig_position {
-            Err(())
+            Err(self.error(expected))
         } else {
             Ok(acc)
         }
@@ -154,104 +357,329 @@ impl<'a> ParseStream<'a> {
         &self.bytes[start..self.position]
     }
 
-    pub fn parse_iter<'b: 'a, T: ParseYolo<'a> + 'a>(mut self, separator: &'b str) -> impl Iterator<Item=T> + 'a {
-        successors(
-            Some(self.parse_yolo().unwrap()),
-            move |_| if self.try_consume(separator) && self.has_next() {
-                Some(self.parse_yolo().unwrap())
-            } else {
-                None
-            },
-        )
+    pub fn parse_iter<'b: 'a, T: ParseYolo<'a> + 'a>(self, separator: &'b str) -> impl Iterator<Item=T> + 'a {
+        self.try_parse_iter(separator).map(Result::unwrap)
+    }
+
+    /// Stops after the first item that fails to parse.
+    pub fn try_parse_iter<'b: 'a, T: ParseYolo<'a> + 'a>(mut self, separator: &'b str) -> impl Iterator<Item=Result<T, ParseError<'a>>> + 'a {
+        let mut done = !self.has_next();
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let item = self.parse_yolo();
+            done = item.is_err() || !(self.try_consume(separator) && self.has_next());
+            Some(item)
+        })
     }
 
     pub fn parse_iter_right_aligned<'b: 'a, T: ParseYolo<'a> + 'b>(&'b mut self) -> impl Iterator<Item=T> + 'a {
-        while self.try_consume(" ") {}
-        successors(
-            Some(self.parse_yolo().unwrap()),
-            |_| if self.try_consume(" ") {
-                while self.try_consume(" ") {}
-                Some(self.parse_yolo().unwrap())
-            } else {
-                None
-            },
-        )
+        self.parse_iter_whitespace()
+    }
+
+    pub fn try_parse_iter_right_aligned<'b: 'a, T: ParseYolo<'a> + 'b>(&'b mut self) -> impl Iterator<Item=Result<T, ParseError<'a>>> + 'a {
+        self.try_parse_iter_whitespace()
+    }
+
+    /// Items separated by any amount of whitespace up to the end of the line, as in column aligned inputs.
+    pub fn parse_iter_whitespace<'b: 'a, T: ParseYolo<'a> + 'b>(&'b mut self) -> impl Iterator<Item=T> + 'a {
+        self.try_parse_iter_whitespace().map(Result::unwrap)
+    }
+
+    /// Stops after the first item that fails to parse.
+    pub fn try_parse_iter_whitespace<'b: 'a, T: ParseYolo<'a> + 'b>(&'b mut self) -> impl Iterator<Item=Result<T, ParseError<'a>>> + 'a {
+        self.skip_whitespace();
+        let mut done = self.at_line_end();
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let item = self.parse_yolo();
+            done = item.is_err() || !self.skip_whitespace() || self.at_line_end();
+            Some(item)
+        })
+    }
+
+    /// Skips spaces and tabs, but not newlines, returning whether there were any.
+    pub fn skip_whitespace(&mut self) -> bool {
+        !self.slice_while(|c| c == b' ' || c == b'\t').is_empty()
+    }
+
+    fn at_line_end(&self) -> bool {
+        matches!(self.peek(), Ok(b'\n') | Err(_))
+    }
+
+    pub fn expect_whitespace(&mut self) -> Result<(), ParseError<'a>> {
+        if self.skip_whitespace() {
+            Ok(())
+        } else {
+            Err(self.error(Expected::Whitespace))
+        }
     }
 
     pub fn drop_until(&mut self, separator: &str) {
+        self.try_drop_until(separator).unwrap()
+    }
+
+    pub fn try_drop_until(&mut self, separator: &str) -> Result<(), ParseError<'a>> {
         let separator_bytes = separator.as_bytes();
-        self.position = self.bytes[self.position..].find(separator_bytes).unwrap() + separator_bytes.len()
+        match self.bytes[self.position..].find(separator_bytes) {
+            Some(offset) => {
+                self.position += offset + separator_bytes.len();
+                Ok(())
+            }
+            None => Err(self.error(Expected::Literal(separator.to_owned()))),
+        }
     }
 
     pub fn parse_header<T: ParseYolo<'a>>(&mut self) -> T {
-        let header = self.parse_yolo().unwrap();
-        self.expect("\n\n").unwrap();
-        header
+        self.try_parse_header().unwrap()
+    }
+
+    pub fn try_parse_header<T: ParseYolo<'a>>(&mut self) -> Result<T, ParseError<'a>> {
+        let header = self.parse_yolo()?;
+        self.expect("\n\n")?;
+        Ok(header)
+    }
+
+    /// Takes the hexadecimal digits at the current position, to be read as bits.
+    pub fn hex_bits(&mut self) -> BitReader<'a> {
+        self.bits(16)
+    }
+
+    /// Takes the binary digits at the current position, to be read as bits.
+    pub fn binary_bits(&mut self) -> BitReader<'a> {
+        self.bits(2)
+    }
+
+    fn bits(&mut self, radix: u32) -> BitReader<'a> {
+        let start = self.position;
+        let digits = self.slice_while(|c| (c as char).is_digit(radix));
+        BitReader { input: self.bytes, start, digits, radix, position: 0 }
+    }
+
+    /// Used by `#[regex]` in the derive. `regex` has to be anchored at the start, and only sees the rest
+    /// of the current line. Moves past the match, returning where each group matched.
+    #[doc(hidden)]
+    pub fn match_regex(&mut self, regex: &Regex, pattern: &'static str) -> Result<Vec<Option<Range<usize>>>, ParseError<'a>> {
+        let base = self.position;
+        let line_end = self.bytes[base..].iter().position(|&c| c == b'\n').map_or(self.bytes.len(), |length| base + length);
+        let captures = regex.captures(&self.bytes[base..line_end]).ok_or_else(|| self.error(Expected::Regex(pattern)))?;
+        let groups = captures.iter()
+            .skip(1)
+            .map(|group| group.map(|group| base + group.start()..base + group.end()))
+            .collect();
+        self.position = base + captures.get(0).unwrap().end();
+        Ok(groups)
+    }
+
+    /// Used by `#[regex]` in the derive. Parses all of a group, or nothing if it didn't match.
+    #[doc(hidden)]
+    pub fn parse_group<T: ParseYolo<'a>>(&self, group: Option<Range<usize>>) -> Result<T, ParseError<'a>> {
+        let group = group.unwrap_or(self.position..self.position);
+        let mut group_stream = ParseStream { bytes: &self.bytes[..group.end], position: group.start };
+        let result = group_stream.parse_yolo().and_then(|value| if group_stream.has_next() {
+            Err(group_stream.error(Expected::Described("the end of the regex group")))
+        } else {
+            Ok(value)
+        });
+        result.map_err(|error| ParseError::new(self.bytes, error.offset, error.expected))
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// What was consumed since the stream was at `start`.
+    pub fn slice_since(&self, start: usize) -> &'a [u8] {
+        &self.bytes[start..self.position]
     }
 
     pub fn has_next(&self) -> bool {
         self.position < self.bytes.len()
     }
 
-    pub fn next(&mut self) -> Result<u8, ()> {
-        if let Some(&result) = self.bytes.get(self.position) {
+    pub fn next(&mut self) -> Result<u8, ParseError<'a>> {
+        let result = self.peek()?;
+        self.position += 1;
+        Ok(result)
+    }
+
+    pub fn peek(&self) -> Result<u8, ParseError<'a>> {
+        self.bytes.get(self.position).copied().ok_or_else(|| self.error(Expected::AnyByte))
+    }
+}
+
+/// Hexadecimal or binary text as a sequence of bits, most significant first.
+pub struct BitReader<'a> {
+    input: &'a [u8],
+    start: usize,
+    digits: &'a [u8],
+    radix: u32,
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// Reads up to 64 bits as a number.
+    pub fn read(&mut self, num_bits: usize) -> Result<u64, ParseError<'a>> {
+        self.ensure_remaining(num_bits)?;
+        let mut result = 0;
+        for _ in 0..num_bits {
+            result = (result << 1) | self.bit(self.position);
             self.position += 1;
-            Ok(result)
+        }
+        Ok(result)
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, ParseError<'a>> {
+        Ok(self.read(1)? == 1)
+    }
+
+    pub fn skip(&mut self, num_bits: usize) -> Result<(), ParseError<'a>> {
+        self.ensure_remaining(num_bits)?;
+        self.position += num_bits;
+        Ok(())
+    }
+
+    /// The number of bits read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.digits.len() * self.bits_per_digit() - self.position
+    }
+
+    fn ensure_remaining(&self, num_bits: usize) -> Result<(), ParseError<'a>> {
+        if num_bits <= self.remaining() {
+            Ok(())
         } else {
-            Err(())
+            Err(ParseError::new(self.input, self.start + self.digits.len(), Expected::Digit))
         }
     }
 
-    pub fn peek(&self) -> Result<u8, ()> {
-        self.bytes.get(self.position).copied().ok_or(())
+    fn bits_per_digit(&self) -> usize {
+        self.radix.trailing_zeros() as usize
+    }
+
+    fn bit(&self, position: usize) -> u64 {
+        let bits_per_digit = self.bits_per_digit();
+        let digit = (self.digits[position / bits_per_digit] as char).to_digit(self.radix).unwrap();
+        ((digit >> (bits_per_digit - 1 - position % bits_per_digit)) & 1) as u64
     }
 }
 
 pub trait ParseYolo<'a> {
-    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ()> where Self: Sized;
+    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> where Self: Sized;
 }
 
-impl ParseYolo<'_> for u64 {
-    fn parse_from_stream(stream: &mut ParseStream) -> Result<Self, ()> {
-        Ok(
-            stream.fold_while(
-                0,
-                |c| c.is_ascii_digit(),
-                |acc, c| acc * 10 + (c - b'0') as u64,
-            )?
-        )
-    }
+macro_rules! parse_yolo_numbers {
+    ($parse:ident: $($number:ty),*) => {
+        $(
+            impl<'a> ParseYolo<'a> for $number {
+                fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> {
+                    $parse(stream, stringify!($number))
+                }
+            }
+        )*
+    };
 }
 
-impl ParseYolo<'_> for usize {
-    fn parse_from_stream(stream: &mut ParseStream) -> Result<Self, ()> {
-        Ok(stream.parse_yolo::<u64>()? as usize)
-    }
+parse_yolo_numbers!(parse_integer: u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+parse_yolo_numbers!(parse_float: f32, f64);
+
+fn parse_integer<'a, T: PrimInt>(stream: &mut ParseStream<'a>, type_name: &'static str) -> Result<T, ParseError<'a>> {
+    parse_integer_in_radix(stream, 10, type_name)
 }
 
-impl ParseYolo<'_> for u32 {
-    fn parse_from_stream(stream: &mut ParseStream) -> Result<Self, ()> {
-        Ok(stream.parse_yolo::<u64>()? as u32)
+/// Accumulates towards the sign of the number, so that the minimum of a signed type still fits.
+fn parse_integer_in_radix<'a, T: PrimInt>(stream: &mut ParseStream<'a>, radix: u32, type_name: &'static str) -> Result<T, ParseError<'a>> {
+    let start = stream.position;
+    let negative = T::min_value() < T::zero() && stream.try_consume("-");
+    if !negative {
+        stream.try_consume("+");
+    }
+    let digits = stream.slice_while(|c| (c as char).is_digit(radix));
+    if digits.is_empty() {
+        return Err(stream.error(Expected::Digit));
     }
+    let base = T::from(radix).unwrap();
+    digits.iter()
+        .try_fold(T::zero(), |acc, &c| {
+            let digit = T::from((c as char).to_digit(radix).unwrap()).unwrap();
+            let shifted = acc.checked_mul(&base)?;
+            if negative { shifted.checked_sub(&digit) } else { shifted.checked_add(&digit) }
+        })
+        .ok_or_else(|| ParseError::new(stream.bytes, start, Expected::InRange(type_name)))
 }
 
-impl ParseYolo<'_> for i64 {
-    fn parse_from_stream(stream: &mut ParseStream) -> Result<Self, ()> {
-        let negative = stream.try_consume("-");
-        let value = stream.parse_yolo::<u64>()? as i64;
-        Ok(if negative { -value } else { value })
+/// Only takes a `.` that is followed by a digit, so that patterns like `{}.` keep working. Values too
+/// large for `T` are an error rather than infinity.
+fn parse_float<'a, T: FromStr + Float>(stream: &mut ParseStream<'a>, type_name: &'static str) -> Result<T, ParseError<'a>> {
+    let start = stream.position;
+    if !stream.try_consume("-") {
+        stream.try_consume("+");
+    }
+    let mut num_digits = stream.slice_while(|c| c.is_ascii_digit()).len();
+    if stream.peek() == Ok(b'.') && stream.bytes.get(stream.position + 1).is_some_and(u8::is_ascii_digit) {
+        stream.position += 1;
+        num_digits += stream.slice_while(|c| c.is_ascii_digit()).len();
+    }
+    if num_digits == 0 {
+        return Err(stream.error(Expected::Digit));
     }
+    let before_exponent = stream.position;
+    if stream.try_consume("e") || stream.try_consume("E") {
+        if !stream.try_consume("-") {
+            stream.try_consume("+");
+        }
+        if stream.slice_while(|c| c.is_ascii_digit()).is_empty() {
+            stream.position = before_exponent;
+        }
+    }
+    std::str::from_utf8(&stream.bytes[start..stream.position]).unwrap()
+        .parse()
+        .ok()
+        .filter(|value: &T| !value.is_infinite())
+        .ok_or_else(|| ParseError::new(stream.bytes, start, Expected::InRange(type_name)))
 }
 
-impl ParseYolo<'_> for isize {
-    fn parse_from_stream(stream: &mut ParseStream) -> Result<Self, ()> {
-        Ok(stream.parse_yolo::<i64>()? as isize)
-    }
+/// A number written in hexadecimal, without a prefix.
+#[derive(Default, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Hex<T>(pub T);
+
+/// A number written in binary, without a prefix.
+#[derive(Default, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Bin<T>(pub T);
+
+/// A number written in octal, without a prefix.
+#[derive(Default, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Oct<T>(pub T);
+
+macro_rules! parse_yolo_radix {
+    ($($wrapper:ident => $radix:literal),*) => {
+        $(
+            impl<'a, T: PrimInt> ParseYolo<'a> for $wrapper<T> {
+                fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> {
+                    Ok(Self(parse_integer_in_radix(stream, $radix, std::any::type_name::<T>())?))
+                }
+            }
+        )*
+    };
 }
 
-impl ParseYolo<'_> for i32 {
-    fn parse_from_stream(stream: &mut ParseStream) -> Result<Self, ()> {
-        Ok(stream.parse_yolo::<i64>()? as i32)
+parse_yolo_radix!(Hex => 16, Bin => 2, Oct => 8);
+
+impl<'a> ParseYolo<'a> for bool {
+    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> {
+        if stream.try_consume("true") {
+            Ok(true)
+        } else if stream.try_consume("false") {
+            Ok(false)
+        } else {
+            Err(stream.error(Expected::OneOf(&["true", "false"])))
+        }
     }
 }
 
@@ -270,38 +698,229 @@ impl<'a> Word<'a> {
 }
 
 impl<'a> ParseYolo<'a> for Word<'a> {
-    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ()> {
+    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> {
         Ok(Self(stream.slice_while(|c| c.is_ascii_lowercase() || c.is_ascii_uppercase())))
     }
 }
 
-impl ParseYolo<'_> for char {
-    fn parse_from_stream(stream: &mut ParseStream<'_>) -> Result<Self, ()> {
+/// Letters, digits and underscores, not starting with a digit.
+#[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub struct Ident<'a>(&'a str);
+
+/// Everything up to the given byte, or to the end of the input, which may be nothing.
+#[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub struct UntilByte<'a, const B: u8>(&'a str);
+
+/// Everything up to the end of the line, without the newline.
+#[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub struct RestOfLine<'a>(&'a str);
+
+/// The text between double quotes. Escaped quotes don't end it, and escapes are kept as they are.
+#[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub struct Quoted<'a>(&'a str);
+
+macro_rules! str_tokens {
+    ($($token:ident $(<const $param:ident>)?),*) => {
+        $(
+            impl<'a $(, const $param: u8)?> $token<'a $(, $param)?> {
+                pub fn as_str(&self) -> &'a str {
+                    self.0
+                }
+            }
+
+            impl<'a $(, const $param: u8)?> From<$token<'a $(, $param)?>> for &'a str {
+                fn from(token: $token<'a $(, $param)?>) -> Self {
+                    token.0
+                }
+            }
+
+            impl<$(const $param: u8)?> PartialEq<str> for $token<'_ $(, $param)?> {
+                fn eq(&self, other: &str) -> bool {
+                    self.0 == other
+                }
+            }
+
+            impl<$(const $param: u8)?> PartialEq<&str> for $token<'_ $(, $param)?> {
+                fn eq(&self, other: &&str) -> bool {
+                    self.0 == *other
+                }
+            }
+
+            impl<$(const $param: u8)?> Display for $token<'_ $(, $param)?> {
+                fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                    f.write_str(self.0)
+                }
+            }
+        )*
+    };
+}
+
+str_tokens!(Ident, UntilByte<const B>, RestOfLine, Quoted);
+
+impl<'a> ParseYolo<'a> for Ident<'a> {
+    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> {
+        if stream.peek().is_ok_and(|c| c.is_ascii_digit()) {
+            return Err(stream.error(Expected::Described("an identifier")));
+        }
+        let start = stream.position;
+        let ident = stream.slice_while(|c| c.is_ascii_alphanumeric() || c == b'_');
+        if ident.is_empty() {
+            return Err(stream.error(Expected::Described("an identifier")));
+        }
+        Ok(Self(to_str(stream, ident, start)?))
+    }
+}
+
+impl<'a, const B: u8> ParseYolo<'a> for UntilByte<'a, B> {
+    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> {
+        let start = stream.position;
+        let text = stream.slice_while(|c| c != B);
+        Ok(Self(to_str(stream, text, start)?))
+    }
+}
+
+impl<'a> ParseYolo<'a> for RestOfLine<'a> {
+    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> {
+        Ok(Self(stream.parse_yolo::<UntilByte<b'\n'>>()?.0))
+    }
+}
+
+impl<'a> ParseYolo<'a> for Quoted<'a> {
+    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> {
+        stream.expect("\"")?;
+        let start = stream.position;
+        let mut end = start;
+        while stream.bytes.get(end).is_some_and(|&c| c != b'"') {
+            end += if stream.bytes[end] == b'\\' { 2 } else { 1 };
+        }
+        if end >= stream.bytes.len() {
+            stream.position = stream.bytes.len();
+            return Err(stream.error(Expected::Literal("\"".to_owned())));
+        }
+        stream.position = end + 1;
+        Ok(Self(to_str(stream, &stream.bytes[start..end], start)?))
+    }
+}
+
+/// A run of anything but whitespace.
+impl<'a> ParseYolo<'a> for &'a str {
+    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> {
+        let start = stream.position;
+        let text = stream.slice_while(|c| !c.is_ascii_whitespace());
+        if text.is_empty() {
+            return Err(stream.error(Expected::Described("a token")));
+        }
+        to_str(stream, text, start)
+    }
+}
+
+/// `text` starts at `start` in the stream.
+fn to_str<'a>(stream: &ParseStream<'a>, text: &'a [u8], start: usize) -> Result<&'a str, ParseError<'a>> {
+    std::str::from_utf8(text)
+        .map_err(|error| ParseError::new(stream.bytes, start + error.valid_up_to(), Expected::Described("valid UTF-8")))
+}
+
+impl<'a> ParseYolo<'a> for char {
+    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> {
         Ok(stream.next()? as char)
     }
 }
 
 impl<'a, T: ParseYolo<'a>> ParseYolo<'a> for Box<T> {
-    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ()> where Self: Sized {
+    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> where Self: Sized {
         Ok(Box::new(stream.parse_yolo()?))
     }
 }
 
+/// An optional fragment: `None` if `T` doesn't parse here, consuming nothing.
+impl<'a, T: ParseYolo<'a>> ParseYolo<'a> for Option<T> {
+    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> where Self: Sized {
+        Ok(stream.parse_yolo().ok())
+    }
+}
+
+/// Tuples are parsed one item right after the other, or with a separator in between.
+macro_rules! parse_yolo_tuples {
+    ($(($first:ident $(, $rest:ident)+)),*) => {
+        $(
+            impl<'a, $first: ParseYolo<'a> $(, $rest: ParseYolo<'a>)+> ParseYolo<'a> for ($first $(, $rest)+) {
+                fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> {
+                    Ok((stream.parse_yolo::<$first>()? $(, stream.parse_yolo::<$rest>()?)+))
+                }
+            }
+
+            impl<'a, $first: ParseYolo<'a> $(, $rest: ParseYolo<'a>)+> ParseSeparated<'a> for ($first $(, $rest)+) {
+                fn parse_from_stream(stream: &mut ParseStream<'a>, separator: &str) -> Result<Self, ParseError<'a>> {
+                    Ok((stream.parse_yolo::<$first>()? $(, {
+                        stream.expect(separator)?;
+                        stream.parse_yolo::<$rest>()?
+                    })+))
+                }
+            }
+        )*
+    };
+}
+
+parse_yolo_tuples!((A, B), (A, B, C), (A, B, C, D));
+
 
 pub trait ParseSeparated<'a> {
-    fn parse_from_stream(stream: &mut ParseStream<'a>, separator: &str) -> Result<Self, ()> where Self: Sized;
+    fn parse_from_stream(stream: &mut ParseStream<'a>, separator: &str) -> Result<Self, ParseError<'a>> where Self: Sized;
 }
 
 
-impl<'a, T: Default + Copy + ParseYolo<'a>, const N: usize> ParseSeparated<'a> for [T; N] {
-    fn parse_from_stream(stream: &mut ParseStream<'a>, separator: &str) -> Result<Self, ()> where Self: Sized {
-        let mut result = [T::default(); N];
-        for i in 0..(N - 1) {
-            result[i] = stream.parse_yolo()?;
-            stream.expect(separator)?;
+impl<'a, T: ParseYolo<'a>, const N: usize> ParseSeparated<'a> for [T; N] {
+    fn parse_from_stream(stream: &mut ParseStream<'a>, separator: &str) -> Result<Self, ParseError<'a>> where Self: Sized {
+        let mut items = heapless::Vec::<T, N>::new();
+        for i in 0..N {
+            if i > 0 {
+                stream.expect(separator)?;
+            }
+            _ = items.push(stream.parse_yolo()?);
+        }
+        Ok(items.into_array().unwrap_or_else(|_| unreachable!()))
+    }
+}
+
+/// As many items as there are, possibly none.
+impl<'a, T: ParseYolo<'a>> ParseSeparated<'a> for Vec<T> {
+    fn parse_from_stream(stream: &mut ParseStream<'a>, separator: &str) -> Result<Self, ParseError<'a>> where Self: Sized {
+        let mut items = Vec::new();
+        parse_items(stream, separator, usize::MAX, |item| items.push(item));
+        Ok(items)
+    }
+}
+
+/// Up to `N` items, leaving any further ones in the stream.
+impl<'a, T: ParseYolo<'a>, const N: usize> ParseSeparated<'a> for heapless::Vec<T, N> {
+    fn parse_from_stream(stream: &mut ParseStream<'a>, separator: &str) -> Result<Self, ParseError<'a>> where Self: Sized {
+        let mut items = heapless::Vec::new();
+        parse_items(stream, separator, N, |item| _ = items.push(item));
+        Ok(items)
+    }
+}
+
+impl<'a, T: ParseSeparated<'a>> ParseSeparated<'a> for Option<T> {
+    fn parse_from_stream(stream: &mut ParseStream<'a>, separator: &str) -> Result<Self, ParseError<'a>> where Self: Sized {
+        Ok(stream.parse_separated(separator).ok())
+    }
+}
+
+/// Stops before a separator that isn't followed by an item.
+fn parse_items<'a, T: ParseYolo<'a>, F: FnMut(T)>(stream: &mut ParseStream<'a>, separator: &str, max_items: usize, mut add: F) {
+    for i in 0..max_items {
+        let item = if i == 0 {
+            stream.parse_yolo()
+        } else {
+            stream.try_parse(|stream| {
+                stream.expect(separator)?;
+                stream.parse_yolo()
+            })
+        };
+        match item {
+            Ok(item) => add(item),
+            Err(_) => break,
         }
-        result[N - 1] = stream.parse_yolo()?;
-        Ok(result)
     }
 }
 
@@ -525,6 +1144,8 @@ impl<T> VecDequeExtras<T> for VecDeque<T> {
 
 #[cfg(test)]
 mod tests {
+    use parse_yolo_derive::ParseYolo;
+
     use super::*;
 
     #[test]
@@ -538,4 +1159,314 @@ mod tests {
 
         assert_eq!(unindented, "aa\nbb\n\ncc\n")
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn parse_error_points_at_the_offending_text() {
+        let mut stream = ParseStream::new(b"12,5\n3;4");
+        stream.parse_separated::<[u64; 2]>(",").unwrap();
+        stream.expect("\n").unwrap();
+
+        let error = stream.parse_separated::<[u64; 2]>(",").unwrap_err();
+
+        assert_eq!(error.offset(), 6);
+        assert_eq!((error.line(), error.column()), (2, 2));
+        assert_eq!(error.expected(), &Expected::Literal(",".to_owned()));
+        assert_eq!(error.to_string(), "expected \",\" at line 2, column 2\n3;4\n ^");
+    }
+
+    #[test]
+    fn parse_error_names_the_enum_variants() {
+        #[derive(ParseYolo, Debug)]
+        enum Direction {
+            Up,
+            Down,
+        }
+
+        let error = ParseStream::new(b"left").parse_yolo::<Direction>().unwrap_err();
+
+        assert_eq!(error.expected(), &Expected::OneOf(&["Up", "Down"]));
+        assert_eq!(error.snippet(), "left");
+    }
+
+    #[test]
+    fn missing_number_expects_a_digit() {
+        let error = ParseStream::new(b"-x").parse_yolo::<i32>().unwrap_err();
+
+        assert_eq!((error.offset(), error.expected()), (1, &Expected::Digit));
+    }
+
+    #[test]
+    fn empty_input_gives_no_items() {
+        assert_eq!(ParseStream::new(b"").parse_iter::<u64>(",").count(), 0);
+        assert_eq!(ParseStream::new(b"   ").parse_iter_right_aligned::<u64>().count(), 0);
+        assert_eq!(InputData::from_string("").lines_as::<u64>().count(), 0);
+    }
+
+    #[test]
+    fn try_parse_iter_stops_at_the_first_error() {
+        let result = ParseStream::new(b"1,2,x,4").try_parse_iter::<u64>(",").collect_vec();
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[1], Ok(2));
+        assert_eq!(result[2].as_ref().unwrap_err().offset(), 4);
+    }
+
+    #[test]
+    fn try_lines_as_reports_bad_lines() {
+        let input = InputData::from_string("
+            1
+            two
+        ");
+
+        let result = input.try_lines_as::<u64>().collect_vec();
+
+        assert_eq!(result[0], Ok(1));
+        assert_eq!(result[1].as_ref().unwrap_err().line(), 2);
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        assert!(InputData::try_from_file("does/not/exist").is_err());
+    }
+
+    #[test]
+    fn integers_that_do_not_fit_are_errors() {
+        assert_eq!(ParseStream::new(b"255").parse_yolo::<u8>(), Ok(255));
+        assert_eq!(ParseStream::new(b"+7").parse_yolo::<u16>(), Ok(7));
+        assert_eq!(ParseStream::new(b"-128").parse_yolo::<i8>(), Ok(i8::MIN));
+        assert_eq!(ParseStream::new(b"-170141183460469231731687303715884105728").parse_yolo::<i128>(), Ok(i128::MIN));
+        assert_eq!(ParseStream::new(b"256").parse_yolo::<u8>().unwrap_err().expected(), &Expected::InRange("u8"));
+        assert_eq!(ParseStream::new(b"128").parse_yolo::<i8>().unwrap_err().expected(), &Expected::InRange("i8"));
+        assert_eq!(ParseStream::new(b"-1").parse_yolo::<u32>().unwrap_err().expected(), &Expected::Digit);
+    }
+
+    #[test]
+    fn floats_work() {
+        assert_eq!(ParseStream::new(b"-1.5e3").parse_yolo::<f64>(), Ok(-1500.0));
+        assert_eq!(ParseStream::new(b"+0.25").parse_yolo::<f32>(), Ok(0.25));
+        assert_eq!(ParseStream::new(b"1e39").parse_yolo::<f32>().unwrap_err().expected(), &Expected::InRange("f32"));
+        assert_eq!(ParseStream::new(b"1e39").parse_yolo::<f64>(), Ok(1e39));
+
+        let mut stream = ParseStream::new(b"2.e");
+        assert_eq!(stream.parse_yolo::<f64>(), Ok(2.0));
+        assert!(stream.try_consume(".e"));
+    }
+
+    #[test]
+    fn bools_work() {
+        let mut stream = ParseStream::new(b"true,false");
+
+        assert_eq!(stream.parse_separated::<[bool; 2]>(","), Ok([true, false]));
+    }
+
+    #[test]
+    fn separated_collections_work() {
+        #[derive(ParseYolo)]
+        #[pattern("{} | {} | {}")]
+        struct Line {
+            #[separator(",")] draws: Vec<u64>,
+            #[separator(" ")] boxes: [Box<u8>; 2],
+            #[separator("-")] range: (u8, u8),
+        }
+
+        let line = ParseStream::new(b"7,4,9 | 1 2 | 3-5").parse_yolo::<Line>().unwrap();
+
+        assert_eq!(line.draws, vec![7, 4, 9]);
+        assert_eq!(line.boxes, [Box::new(1), Box::new(2)]);
+        assert_eq!(line.range, (3, 5));
+    }
+
+    #[test]
+    fn separated_collections_leave_the_rest() {
+        let mut stream = ParseStream::new(b"1,2,3,");
+
+        assert_eq!(stream.parse_separated::<heapless::Vec<u8, 2>>(","), Ok(heapless::Vec::from_slice(&[1, 2]).unwrap()));
+        assert_eq!(stream.parse_separated::<Vec<u8>>(","), Ok(vec![]));
+        assert!(stream.try_consume(","));
+        assert_eq!(stream.parse_separated::<Vec<u8>>(","), Ok(vec![3]));
+        assert!(stream.try_consume(","));
+    }
+
+    #[test]
+    fn optional_fragments_work() {
+        let mut stream = ParseStream::new(b"x-3");
+
+        assert_eq!(stream.parse_yolo::<Option<u8>>(), Ok(None));
+        assert_eq!(stream.parse_yolo::<(char, Option<i8>)>(), Ok(('x', Some(-3))));
+    }
+
+    #[test]
+    fn numbers_in_other_radixes_work() {
+        let mut stream = ParseStream::new(b"ff 1011 17 100");
+
+        assert_eq!(stream.parse_separated::<(Hex<u8>, Bin<u8>, Oct<u8>)>(" "), Ok((Hex(255), Bin(11), Oct(15))));
+        assert!(stream.try_consume(" "));
+        assert_eq!(stream.parse_yolo::<Hex<u8>>().unwrap_err().expected(), &Expected::InRange("u8"));
+    }
+
+    #[test]
+    fn bit_reader_works() {
+        let mut bits = ParseStream::new(b"D2FE28").hex_bits();
+
+        assert_eq!(bits.read(3), Ok(6));
+        assert_eq!(bits.read(3), Ok(4));
+        assert_eq!(bits.read_bit(), Ok(true));
+        assert_eq!(bits.read(4), Ok(0b0111));
+        assert_eq!(bits.position(), 11);
+        assert_eq!(bits.remaining(), 13);
+        assert_eq!(bits.read(14).unwrap_err().offset(), 6);
+        assert_eq!(ParseStream::new(b"101").binary_bits().read(3), Ok(5));
+    }
+
+    #[test]
+    fn whitespace_separated_items_work() {
+        let mut stream = ParseStream::new(b" 8\t 2 23  4 \n5");
+
+        assert_eq!(stream.parse_iter_whitespace::<u8>().collect_vec(), vec![8, 2, 23, 4]);
+    }
+
+    #[test]
+    fn flexible_whitespace_patterns_work() {
+        #[derive(ParseYolo, Debug, PartialEq)]
+        #[flexible_whitespace]
+        #[pattern("{} -> {}")]
+        struct Edge {
+            from: u8,
+            to: u8,
+        }
+
+        assert_eq!(ParseStream::new(b"3   ->\t4").parse_yolo(), Ok(Edge { from: 3, to: 4 }));
+        assert_eq!(ParseStream::new(b"3->4").parse_yolo::<Edge>().unwrap_err().expected(), &Expected::Whitespace);
+    }
+
+    #[test]
+    fn idents_and_rest_of_line_work() {
+        #[derive(ParseYolo)]
+        #[pattern("{} = {}")]
+        struct Assignment<'a> {
+            name: Ident<'a>,
+            value: RestOfLine<'a>,
+        }
+
+        let assignment = ParseStream::new(b"x_1 = a, b\nnext").parse_yolo::<Assignment>().unwrap();
+
+        assert_eq!(assignment.name, "x_1");
+        assert_eq!(assignment.value.as_str(), "a, b");
+        assert!(ParseStream::new(b"1x").parse_yolo::<Ident>().is_err());
+    }
+
+    #[test]
+    fn until_byte_and_quoted_work() {
+        let mut stream = ParseStream::new(br#"abc,"say \"hi\"","open"#);
+
+        assert_eq!(stream.parse_yolo::<UntilByte<b','>>().unwrap(), "abc");
+        stream.expect(",").unwrap();
+        assert_eq!(stream.parse_yolo::<Quoted>().unwrap(), r#"say \"hi\""#);
+        stream.expect(",").unwrap();
+        assert_eq!(stream.parse_yolo::<Quoted>().unwrap_err().expected(), &Expected::Literal("\"".to_owned()));
+    }
+
+    #[test]
+    fn str_tokens_must_be_utf8() {
+        let mut stream = ParseStream::new("héllo wörld".as_bytes());
+
+        assert_eq!(stream.parse_yolo::<&str>(), Ok("héllo"));
+        assert_eq!(ParseStream::new(b"ab\xff").parse_yolo::<&str>().unwrap_err().offset(), 2);
+    }
+
+    #[test]
+    fn regex_fields_work() {
+        #[derive(ParseYolo, Debug, PartialEq)]
+        #[regex(r"(\w+) (?:has|have) (\d+)(?: \((\d+) spare\))?")]
+        struct Stock<'a> {
+            item: Ident<'a>,
+            count: u32,
+            spare: Option<u32>,
+        }
+
+        let input = InputData::from_string("
+            apples have 3 (1 spare)
+            pear has 2
+        ");
+
+        let result = input.lines_as::<Stock>().map(|stock| (stock.item.as_str(), stock.count, stock.spare)).collect_vec();
+
+        assert_eq!(result, vec![("apples", 3, Some(1)), ("pear", 2, None)]);
+        assert_eq!(ParseStream::new(b"pear has none").parse_yolo::<Stock>().unwrap_err().expected(), &Expected::Regex(r"(\w+) (?:has|have) (\d+)(?: \((\d+) spare\))?"));
+        assert_eq!(ParseStream::new(b"pear has 9999999999").parse_yolo::<Stock>().unwrap_err().expected(), &Expected::InRange("u32"));
+    }
+
+    #[test]
+    fn regex_stays_on_line() {
+        #[derive(ParseYolo, Debug, PartialEq)]
+        #[regex(r"(\d+)\s+(\d+)")]
+        struct Pair {
+            a: u32,
+            b: u32,
+        }
+
+        assert_eq!(ParseStream::new(b"1 \t2\n3").parse_yolo::<Pair>(), Ok(Pair { a: 1, b: 2 }));
+        assert_eq!(ParseStream::new(b"1\n2").parse_yolo::<Pair>().unwrap_err().expected(), &Expected::Regex(r"(\d+)\s+(\d+)"));
+    }
+
+    #[test]
+    fn regex_fields_can_have_any_name() {
+        #[derive(ParseYolo, Debug, PartialEq)]
+        #[regex(r"(\d+) (\d+)")]
+        struct Counts {
+            groups: u32,
+            regex: u32,
+        }
+
+        assert_eq!(ParseStream::new(b"3 4").parse_yolo::<Counts>(), Ok(Counts { groups: 3, regex: 4 }));
+    }
+
+    #[test]
+    fn regex_variants_work() {
+        #[derive(ParseYolo, Debug, PartialEq)]
+        enum Command {
+            #[regex(r"move (-?\d+)(?:,(-?\d+))?")]
+            Move(i32, Option<i32>),
+            #[regex(r"wait|sleep")]
+            Wait,
+        }
+
+        assert_eq!(ParseStream::new(b"move 3,-4").parse_yolo(), Ok(Command::Move(3, Some(-4))));
+        assert_eq!(ParseStream::new(b"sleep").parse_yolo(), Ok(Command::Wait));
+        assert_eq!(ParseStream::new(b"move x").parse_yolo::<Command>().unwrap_err().expected(), &Expected::OneOf(&["Move", "Wait"]));
+        assert_eq!(ParseStream::new(b"move 3000000000").parse_yolo::<Command>().unwrap_err().offset(), 0);
+    }
+
+    #[test]
+    fn sections_work() {
+        let input = InputData::from_string("
+            1,2
+
+            3
+            4
+
+
+            ab
+            cd
+        ");
+
+        let [numbers, more_numbers, grid] = input.split_sections();
+
+        assert_eq!(numbers.stream().parse_iter::<u8>(",").collect_vec(), vec![1, 2]);
+        assert_eq!(more_numbers.lines_as::<u8>().collect_vec(), vec![3, 4]);
+        assert_eq!(grid.grid(|c| c as char).rows().map(|row| row.iter().collect::<String>()).collect_vec(), vec!["ab", "cd"]);
+    }
+
+    #[test]
+    fn wrong_number_of_sections_is_an_error() {
+        let input = InputData::from_string("
+            a
+
+            b
+        ");
+
+        let error = input.try_split_sections::<3>().unwrap_err();
+
+        assert_eq!(error.to_string(), "Expected 3 sections separated by blank lines, found 2");
+    }
+}
@@ -0,0 +1,90 @@
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+use std::sync::mpsc;
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::Duration;
+
+use crate::benchmark::format_duration;
+
+/// Solutions may recurse deeply, and spawned threads get a much smaller stack than the main one by default.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Failure {
+    Panic(String),
+    Timeout(Duration),
+    /// Not run at all, because its input could not be found or read.
+    NoInput(String),
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failure::Panic(message) => write!(f, "PANIC: {}", message),
+            Failure::Timeout(timeout) => write!(f, "TIMEOUT after {}", format_duration(*timeout)),
+            Failure::NoInput(message) => f.write_str(message),
+        }
+    }
+}
+
+/// Runs `f` on its own thread, turning a panic into an error. After `timeout` the thread is abandoned
+/// and keeps running in the background until the process exits, using up a core while it does.
+pub fn isolated<T: Send + 'static, F: FnOnce() -> T + Send + 'static>(timeout: Option<Duration>, f: F) -> Result<T, Failure> {
+    let (sender, receiver) = mpsc::channel();
+    let handle = thread::Builder::new()
+        .name("solution".to_owned())
+        .stack_size(STACK_SIZE)
+        .spawn(move || _ = sender.send(f()))
+        .unwrap();
+    let result = match timeout {
+        Some(timeout) => receiver.recv_timeout(timeout),
+        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    };
+    match result {
+        Ok(value) => {
+            _ = handle.join();
+            Ok(value)
+        }
+        Err(RecvTimeoutError::Timeout) => Err(Failure::Timeout(timeout.unwrap())),
+        Err(RecvTimeoutError::Disconnected) => Err(Failure::Panic(panic_message(handle.join().err().unwrap()))),
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_owned()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn result_is_returned() {
+        let result = isolated(Some(Duration::from_secs(10)), || 42);
+
+        assert_eq!(result, Ok(42));
+    }
+
+    #[test]
+    fn panic_is_captured() {
+        let result = isolated(None, || -> u8 { panic!("Too many items in {}", "iterator") });
+
+        assert_eq!(result, Err(Failure::Panic("Too many items in iterator".to_owned())));
+    }
+
+    #[test]
+    fn slow_run_times_out() {
+        let result = isolated(Some(Duration::from_millis(10)), || thread::sleep(Duration::from_secs(1)));
+
+        assert_eq!(result, Err(Failure::Timeout(Duration::from_millis(10))));
+        assert_eq!(result.err().unwrap().to_string(), "TIMEOUT after 10.0 ms");
+    }
+}
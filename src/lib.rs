@@ -0,0 +1,19 @@
+//! Parsing and data structure helpers for puzzle solutions, usable from other crates.
+
+// `ParseStream::next` returns a `Result`, so it can't be an iterator.
+#![allow(clippy::should_implement_trait)]
+
+// Lets the `ParseYolo` derive name this crate the same way inside it as everywhere else.
+extern crate self as advent_of_rust;
+
+pub mod input;
+pub mod combinators;
+pub mod array;
+pub mod collections;
+pub mod graph;
+
+pub use parse_yolo_derive::ParseYolo;
+
+// For the code generated by `#[regex]`.
+#[doc(hidden)]
+pub use regex;
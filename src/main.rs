@@ -1,52 +1,468 @@
-mod year2021;
-mod input;
-mod array;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::exit;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::{Parser, Subcommand};
+use itertools::Itertools;
+
+use advent_of_rust::{array, collections, graph, input, u8_map};
+
+use crate::answer::Answer;
+use crate::answer_store::{ANSWERS_FILE, AnswerStore, escape, hash_input, KnownAnswer};
+use crate::benchmark::{Failed, Format, Measurement, PARSE_PART, README_FILE};
+use crate::client::{Client, Fetched};
+use crate::config::{Config, CONFIG_FILE};
+use crate::isolation::{Failure, isolated};
+use crate::registry::{Registry, SolutionKey};
+use crate::resolver::{InputResolver, read_input};
+use crate::submissions::{Submission, SUBMISSIONS_FILE, SubmissionLog, Verdict};
+
+mod alloc_counter;
+mod answer;
+mod answer_store;
 mod benchmark;
-mod collections;
-mod graph;
+mod registry;
+mod scaffold;
+mod config;
+mod resolver;
+mod client;
+mod submissions;
+mod isolation;
+mod pool;
+#[cfg(test)]
+mod test_support;
+
+#[cfg(feature = "alloc-profile")]
+#[global_allocator]
+static ALLOCATOR: alloc_counter::CountingAllocator = alloc_counter::CountingAllocator;
 
-#[allow(unused_macros)]
 macro_rules! run {
-    ($year:ident, $day:ident, $part:ident) => {
+    ($solution:expr, $input:expr) => {
         {
-            let input_data = crate::input::InputData::from_file(&format!("input/{}/{}", stringify!($year), stringify!($day)));
-            let start_time = std::time::SystemTime::now();
-            let result = $year::$day::$part(&input_data);
-            println!("Computation took {} μs", start_time.elapsed().unwrap().as_micros() as f64);
+            let start_time = std::time::Instant::now();
+            let result = $solution($input);
+            println!("Computation took {}", benchmark::format_duration(start_time.elapsed()));
             result
         }
     };
 }
 
+macro_rules! years {
+    ($($year:ident),* $(,)?) => {
+        $(mod $year;)*
+
+        fn registry() -> Registry {
+            let mut registry = Registry::new();
+            $($year::register(&mut registry);)*
+            registry
+        }
+    };
+}
+
+years!(
+    year2021,
+);
+
+#[derive(Parser)]
+#[command(about = "Advent of Code solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Prints the answers for a single day
+    Run {
+        year: u16,
+        day: u8,
+        part: Option<u8>,
+        /// Read the input from this file instead of searching for it, `-` for stdin
+        #[arg(long)]
+        input: Option<String>,
+    },
+    /// Prints the timing table for the given days, or for all of them
+    Bench {
+        /// Only benchmark this year; defaults to the latest year when days are given
+        #[arg(long)]
+        year: Option<u16>,
+        days: Vec<u8>,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Write the results to this file in CSV, for a later --compare
+        #[arg(long)]
+        save_baseline: Option<String>,
+        /// Compare medians with a baseline file and fail on regressions
+        #[arg(long)]
+        compare: Option<String>,
+        /// How many percent slower than the baseline counts as a regression
+        #[arg(long, default_value_t = 10.)]
+        threshold: f64,
+        /// Put the medians into the tables in README.md, keeping the rows of parts not measured
+        #[arg(long)]
+        update_readme: bool,
+        /// Give up on a part whose single run takes longer than this many seconds
+        #[arg(long)]
+        timeout: Option<f64>,
+    },
+    /// Compares the answers of the given days, or of all of them, with the ones stored in answers.txt;
+    /// parts are solved in parallel
+    Check {
+        /// Only check this year; defaults to the latest year when days are given
+        #[arg(long)]
+        year: Option<u16>,
+        days: Vec<u8>,
+        /// Store answers that are new or were computed from a different input
+        #[arg(long)]
+        record: bool,
+        /// Give up on a part that takes longer than this many seconds
+        #[arg(long)]
+        timeout: Option<f64>,
+        /// How many parts to solve at the same time; defaults to the number of CPUs
+        #[arg(long, short)]
+        jobs: Option<usize>,
+    },
+    /// Creates a new day from the template and registers it
+    New {
+        year: u16,
+        day: u8,
+    },
+    /// Downloads the input of a day, unless it is already present
+    Fetch {
+        year: u16,
+        day: u8,
+    },
+    /// Computes the answer for a part and submits it, unless earlier submissions show it is wrong
+    Submit {
+        year: u16,
+        day: u8,
+        part: u8,
+    },
+}
+
 fn main() {
-    // let result = run!(year2021, day22, part_2);
-    // println!("{}", result);
-    // TODO
-    benchmark_all!(
-        day01,
-        day02,
-        day03,
-        day04,
-        day05,
-        day06,
-        day07,
-        day08,
-        day09,
-        day10,
-        day11,
-        day12,
-        day13,
-        day14,
-        day15,
-        day16,
-        day17,
-        day18,
-        day19,
-        day20,
-        day21,
-        day22,
-        day23,
-        day24,
-        day25
-    );
+    let registry = registry();
+    let config = Config::load(CONFIG_FILE).unwrap_or_else(|error| fail(&error.to_string()));
+    let resolver = InputResolver::from_config(&config);
+    match Cli::parse().command {
+        Command::Run { year, day, part, input } => run_day(&registry, &resolver, year, day, part, input),
+        Command::Bench { year, days, format, save_baseline, compare, threshold, update_readme, timeout } => {
+            let (measurements, num_failed) = benchmark_days(&registry, &resolver, select_days(&registry, year, days), format, seconds(timeout));
+            report_benchmark(&measurements, format, save_baseline, compare, threshold, update_readme);
+            if num_failed > 0 {
+                fail(&format!("{} parts failed", num_failed));
+            }
+        }
+        Command::Check { year, days, record, timeout, jobs } => {
+            let num_jobs = jobs.unwrap_or_else(pool::default_num_workers);
+            check_answers(&registry, &resolver, select_days(&registry, year, days), record, seconds(timeout), num_jobs);
+        }
+        Command::New { year, day } => new_day(year, day),
+        Command::Fetch { year, day } => fetch_input(&config, &resolver, year, day),
+        Command::Submit { year, day, part } => submit_answer(&registry, &config, &resolver, SolutionKey { year, day, part }),
+    }
+}
+
+fn run_day(registry: &Registry, resolver: &InputResolver, year: u16, day: u8, part: Option<u8>, input: Option<String>) {
+    let parts = part.map(|part| vec![part]).unwrap_or_else(|| vec![1, 2]);
+    let solutions = parts.iter()
+        .map(|&part| registry.get(year, day, part)
+            .unwrap_or_else(|| fail(&format!("No solution for year {} day {} part {}", year, day, part)))
+        )
+        .collect::<Vec<_>>();
+    let input = match input {
+        Some(path) => read_input(&path),
+        None => resolver.resolve(year, day),
+    }.unwrap_or_else(|error| fail(&error.to_string()));
+    for solution in solutions {
+        let result = run!(solution, &input);
+        println!("{}", result);
+    }
+}
+
+/// Resolves the command line selection into the days to process for each year.
+fn select_days(registry: &Registry, year: Option<u16>, days: Vec<u8>) -> Vec<(u16, Vec<u8>)> {
+    let years = match year {
+        Some(year) => vec![year],
+        None if days.is_empty() => registry.years().collect(),
+        None => registry.years().last().into_iter().collect(),
+    };
+    years.into_iter()
+        .map(|year| {
+            let registered = registry.days(year).collect_vec();
+            if registered.is_empty() {
+                fail(&format!("No solutions for year {}", year));
+            }
+            if let Some(day) = days.iter().find(|day| !registered.contains(day)) {
+                fail(&format!("No solution for year {} day {}", year, day));
+            }
+            (year, if days.is_empty() { registered } else { days.clone() })
+        })
+        .collect()
+}
+
+fn seconds(seconds: Option<f64>) -> Option<Duration> {
+    seconds.map(Duration::from_secs_f64)
+}
+
+/// Returns the measurements of the parts that did not fail, and the number of those that did.
+fn benchmark_days(registry: &Registry, resolver: &InputResolver, selection: Vec<(u16, Vec<u8>)>, format: Format, timeout: Option<Duration>) -> (Vec<Measurement>, usize) {
+    let mut measurements = Vec::new();
+    let mut num_failed = 0;
+    let mut timed_out = false;
+    for (year, days) in selection {
+        if let Format::Text = format {
+            benchmark::print_header(year);
+        }
+        for day in days {
+            let results = match resolver.resolve(year, day) {
+                Ok(input) => benchmark_day(registry, year, day, Arc::new(input), timeout),
+                Err(error) => {
+                    let part = if registry.phases(year, day).is_some() { PARSE_PART } else { 1 };
+                    vec![Err(Failed { key: SolutionKey { year, day, part }, failure: Failure::NoInput(error.to_string()) })]
+                }
+            };
+
+            match format {
+                Format::Text => benchmark::print_day(day, &results),
+                Format::Json | Format::Csv => for failed in results.iter().filter_map(|result| result.as_ref().err()) {
+                    eprintln!("{} day {:02} part {}: {}", failed.key.year, failed.key.day, failed.key.part, failed.failure);
+                },
+            }
+            if !timed_out && results.iter().any(|result| matches!(result, Err(Failed { failure: Failure::Timeout(_), .. }))) {
+                // The abandoned thread competes with everything measured after it.
+                eprintln!("warning: a part timed out and keeps running in the background, later timings may be skewed");
+                timed_out = true;
+            }
+            for result in results {
+                match result {
+                    Ok(measurement) => measurements.push(measurement),
+                    Err(_) => num_failed += 1,
+                }
+            }
+        }
+        if let Format::Text = format {
+            let missing_days = registry.missing_days(year).join(", ");
+            if !missing_days.is_empty() {
+                println!("missing days: {}", missing_days);
+            }
+            println!();
+        }
+    }
+    match format {
+        Format::Text => {}
+        Format::Json => print!("{}", benchmark::to_json(&measurements)),
+        Format::Csv => print!("{}", benchmark::to_csv(&measurements)),
+    }
+    (measurements, num_failed)
+}
+
+/// Measures the parts of a day, and the parse step if the day has one.
+fn benchmark_day(registry: &Registry, year: u16, day: u8, input: Arc<input::InputData>, timeout: Option<Duration>) -> Vec<Result<Measurement, Failed>> {
+    let (Some(part_1), Some(part_2)) = (registry.get(year, day, 1), registry.get(year, day, 2)) else {
+        fail(&format!("No solution for year {} day {}", year, day));
+    };
+    match registry.phases(year, day) {
+        Some(phases) => {
+            let parse_key = SolutionKey { year, day, part: PARSE_PART };
+            let (parse, parse_input) = (phases.parse.clone(), input.clone());
+            match isolated(timeout, move || parse(&parse_input)) {
+                Ok(parsed) => vec![
+                    measure_part(parse_key, phases.parse.clone(), input, None),
+                    measure_part(SolutionKey { year, day, part: 1 }, phases.parts[0].clone(), parsed.clone(), timeout),
+                    measure_part(SolutionKey { year, day, part: 2 }, phases.parts[1].clone(), parsed, timeout),
+                ],
+                Err(failure) => vec![Err(Failed { key: parse_key, failure })],
+            }
+        }
+        None => vec![
+            measure_part(SolutionKey { year, day, part: 1 }, part_1.clone(), input.clone(), timeout),
+            measure_part(SolutionKey { year, day, part: 2 }, part_2.clone(), input, timeout),
+        ],
+    }
+}
+
+/// Measures a part on a thread of its own, after checking that a single run finishes within `timeout`.
+fn measure_part<S: ?Sized + Send + Sync + 'static, T: 'static>(
+    key: SolutionKey,
+    solution: Arc<dyn Fn(&S) -> T + Send + Sync>,
+    input: Arc<S>,
+    timeout: Option<Duration>,
+) -> Result<Measurement, Failed> {
+    if timeout.is_some() {
+        let (probe, probe_input) = (solution.clone(), input.clone());
+        isolated(timeout, move || _ = probe(&probe_input)).map_err(|failure| Failed { key, failure })?;
+    }
+    isolated(None, move || Measurement {
+        key,
+        stats: benchmark::benchmark_run(&*solution, &*input),
+        alloc: benchmark::profile_allocations(&*solution, &*input),
+    }).map_err(|failure| Failed { key, failure })
+}
+
+fn report_benchmark(measurements: &[Measurement], format: Format, save_baseline: Option<String>, compare: Option<String>, threshold: f64, update_readme: bool) {
+    if let Some(path) = save_baseline {
+        fs::write(&path, benchmark::to_csv(measurements)).unwrap_or_else(|error| fail(&format!("Could not write {}: {}", path, error)));
+    }
+    if update_readme {
+        let mut readme = fs::read_to_string(README_FILE).unwrap_or_else(|error| fail(&format!("Could not read {}: {}", README_FILE, error)));
+        for (year, year_measurements) in &measurements.iter().group_by(|measurement| measurement.key.year) {
+            readme = benchmark::update_readme(&readme, year, &year_measurements.copied().collect_vec());
+        }
+        fs::write(README_FILE, readme).unwrap_or_else(|error| fail(&format!("Could not write {}: {}", README_FILE, error)));
+    }
+    if let Some(path) = compare {
+        let contents = fs::read_to_string(&path).unwrap_or_else(|error| fail(&format!("Could not read {}: {}", path, error)));
+        let baseline = benchmark::parse_csv(&contents).unwrap_or_else(|line| fail(&format!("Malformed line in {}: {}", path, line)));
+        let comparisons = benchmark::compare(measurements, &baseline, threshold);
+        for comparison in &comparisons {
+            // Keep machine-readable output on stdout parseable.
+            match format {
+                Format::Text => println!("{}", benchmark::format_comparison(comparison)),
+                Format::Json | Format::Csv => eprintln!("{}", benchmark::format_comparison(comparison)),
+            }
+        }
+        let num_regressed = comparisons.iter().filter(|comparison| comparison.regressed).count();
+        if num_regressed > 0 {
+            fail(&format!("{} parts regressed by more than {}%", num_regressed, threshold));
+        }
+    }
+}
+
+enum CheckStatus {
+    Ok,
+    Wrong(Answer),
+    Stale,
+    New,
+}
+
+/// Solves all parts on `num_jobs` threads, then goes through the answers in order.
+fn check_answers(registry: &Registry, resolver: &InputResolver, selection: Vec<(u16, Vec<u8>)>, record: bool, timeout: Option<Duration>, num_jobs: usize) {
+    let mut store = AnswerStore::load(ANSWERS_FILE).unwrap_or_else(|error| fail(&error.to_string()));
+    let days = selection.into_iter()
+        .flat_map(|(year, days)| days.into_iter().map(move |day| (year, day)))
+        .map(|(year, day)| (year, day, resolver.resolve(year, day).map(Arc::new)))
+        .collect_vec();
+    let mut keys = Vec::new();
+    let mut jobs = Vec::new();
+    for (year, day, input) in &days {
+        let Ok(input) = input else {
+            continue;
+        };
+        for part in 1..=2 {
+            if let Some(solution) = registry.get(*year, *day, part) {
+                let (solution, input) = (solution.clone(), input.clone());
+                keys.push(SolutionKey { year: *year, day: *day, part });
+                jobs.push(move || isolated(timeout, move || solution(&input)));
+            }
+        }
+    }
+    let mut answers = keys.into_iter().zip(pool::run_all(num_jobs, jobs)).collect::<BTreeMap<_, _>>();
+
+    let mut num_wrong = 0;
+    let mut num_failed = 0;
+    for (year, day, input) in days {
+        let input = match input {
+            Ok(input) => input,
+            Err(error) => {
+                println!("{} day {:02}: {}", year, day, error);
+                continue;
+            }
+        };
+        let input_hash = hash_input(input.raw());
+        for part in 1..=2 {
+            let key = SolutionKey { year, day, part };
+            let Some(result) = answers.remove(&key) else {
+                continue;
+            };
+            let answer = match result {
+                Ok(answer) => answer,
+                Err(failure) => {
+                    println!("{} day {:02} part {}: {}", key.year, key.day, key.part, failure);
+                    num_failed += 1;
+                    continue;
+                }
+            };
+            let status = match store.get(key) {
+                Some(known) if known.input_hash != input_hash => CheckStatus::Stale,
+                Some(known) if known.answer == answer => CheckStatus::Ok,
+                Some(known) => CheckStatus::Wrong(known.answer.clone()),
+                None => CheckStatus::New,
+            };
+            match &status {
+                CheckStatus::Ok => println!("{} day {:02} part {}: {} (ok)", key.year, key.day, key.part, escape(&answer)),
+                CheckStatus::Wrong(expected) => {
+                    println!("{} day {:02} part {}: {} (WRONG, expected {})", key.year, key.day, key.part, escape(&answer), escape(expected));
+                    num_wrong += 1;
+                }
+                CheckStatus::Stale => println!("{} day {:02} part {}: {} (stale, input has changed)", key.year, key.day, key.part, escape(&answer)),
+                CheckStatus::New => println!("{} day {:02} part {}: {} (new)", key.year, key.day, key.part, escape(&answer)),
+            }
+            if record && matches!(status, CheckStatus::Stale | CheckStatus::New) {
+                store.insert(key, KnownAnswer { input_hash, answer });
+            }
+        }
+    }
+    if record {
+        store.save(ANSWERS_FILE).unwrap_or_else(|error| fail(&error.to_string()));
+    }
+    if num_wrong > 0 || num_failed > 0 {
+        fail(&format!("{} wrong answers, {} failed parts", num_wrong, num_failed));
+    }
+}
+
+fn new_day(year: u16, day: u8) {
+    let created = scaffold::new_day(Path::new("."), year, day)
+        .unwrap_or_else(|error| fail(&error.to_string()));
+    for path in created {
+        println!("wrote {}", path.display());
+    }
+}
+
+fn fetch_input(config: &Config, resolver: &InputResolver, year: u16, day: u8) {
+    if let Some(path) = resolver.cached_location(year, day) {
+        println!("{} already exists", path);
+        return;
+    }
+    let client = Client::from_config(config).unwrap_or_else(|error| fail(&error.to_string()));
+    match client::fetch_input(&client, resolver, year, day).unwrap_or_else(|error| fail(&error.to_string())) {
+        Fetched::Cached(path) => println!("{} already exists", path),
+        Fetched::Downloaded(path) => println!("wrote {}", path),
+    }
+}
+
+fn submit_answer(registry: &Registry, config: &Config, resolver: &InputResolver, key: SolutionKey) {
+    let solution = registry.get(key.year, key.day, key.part)
+        .unwrap_or_else(|| fail(&format!("No solution for year {} day {} part {}", key.year, key.day, key.part)));
+    let input = resolver.resolve(key.year, key.day).unwrap_or_else(|error| fail(&error.to_string()));
+    let answer = solution(&input);
+    println!("{}", answer);
+
+    let mut log = SubmissionLog::load(SUBMISSIONS_FILE).unwrap_or_else(|error| fail(&error.to_string()));
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    if let Err(refusal) = log.check(key, &answer, now) {
+        fail(&refusal.to_string());
+    }
+    let client = Client::from_config(config).unwrap_or_else(|error| fail(&error.to_string()));
+    let (verdict, wait) = client.submit_answer(key.year, key.day, key.part, &answer)
+        .unwrap_or_else(|error| fail(&error.to_string()));
+    log.push(Submission { time: now, key, answer, verdict, wait });
+    log.save(SUBMISSIONS_FILE).unwrap_or_else(|error| fail(&error.to_string()));
+    let retry = if wait > 0 { format!(", wait {}s before trying again", wait) } else { String::new() };
+    match verdict {
+        Verdict::Right => println!("That's the right answer"),
+        Verdict::Wrong => fail(&format!("That's not the right answer{}", retry)),
+        Verdict::TooHigh => fail(&format!("That's not the right answer, it is too high{}", retry)),
+        Verdict::TooLow => fail(&format!("That's not the right answer, it is too low{}", retry)),
+        Verdict::Wait(seconds) => fail(&format!("Submitted too recently, wait {}s", seconds)),
+        Verdict::AlreadySolved => fail("Already solved"),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    exit(1)
 }
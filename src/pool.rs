@@ -0,0 +1,81 @@
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::thread;
+
+pub fn default_num_workers() -> usize {
+    thread::available_parallelism().map(NonZeroUsize::get).unwrap_or(1)
+}
+
+/// Runs the jobs on up to `num_workers` threads, each taking the next job as soon as it is done with
+/// the previous one. Returns the results in the order of the jobs.
+pub fn run_all<T: Send, F: FnOnce() -> T + Send>(num_workers: usize, jobs: Vec<F>) -> Vec<T> {
+    let num_jobs = jobs.len();
+    let jobs = jobs.into_iter().map(|job| Mutex::new(Some(job))).collect::<Vec<_>>();
+    let next_job = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..num_workers.clamp(1, num_jobs.max(1)) {
+            let sender = sender.clone();
+            let (jobs, next_job) = (&jobs, &next_job);
+            scope.spawn(move || loop {
+                let index = next_job.fetch_add(1, Ordering::Relaxed);
+                let Some(job) = jobs.get(index) else {
+                    break;
+                };
+                let job = job.lock().unwrap().take().unwrap();
+                _ = sender.send((index, job()));
+            });
+        }
+    });
+    drop(sender);
+    let mut results = receiver.into_iter().collect::<Vec<_>>();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Barrier;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn results_are_in_job_order() {
+        let jobs = (0..20u64).map(|i| move || {
+            thread::sleep(Duration::from_millis((20 - i) % 7));
+            i * i
+        }).collect();
+
+        let result = run_all(4, jobs);
+
+        assert_eq!(result, (0..20u64).map(|i| i * i).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn jobs_run_concurrently() {
+        // Would wait forever unless all four jobs run at the same time.
+        let barrier = Barrier::new(4);
+        let jobs = (0..4).map(|i| {
+            let barrier = &barrier;
+            move || {
+                barrier.wait();
+                i
+            }
+        }).collect();
+
+        let result = run_all(4, jobs);
+
+        assert_eq!(result, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn no_jobs_give_no_results() {
+        let result = run_all(4, Vec::<fn() -> u8>::new());
+
+        assert!(result.is_empty());
+    }
+}
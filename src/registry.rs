@@ -0,0 +1,206 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use itertools::Itertools;
+
+use crate::answer::Answer;
+use crate::input::InputData;
+
+/// Shared and thread-safe, so that a part can run on a thread of its own.
+pub type Part = Arc<dyn Fn(&InputData) -> Answer + Send + Sync>;
+
+/// A day whose parts share the work of parsing the input, as an alternative to free `part_1` and
+/// `part_2` functions. The benchmark times `parse` separately from the parts.
+pub trait Solution {
+    type Parsed: Send + Sync + 'static;
+    type Part1: Into<Answer>;
+    type Part2: Into<Answer>;
+
+    fn parse(input: &InputData) -> Self::Parsed;
+
+    fn part_1(parsed: &Self::Parsed) -> Self::Part1;
+
+    fn part_2(parsed: &Self::Parsed) -> Self::Part2;
+}
+
+pub type Parsed = Arc<dyn Any + Send + Sync>;
+pub type Parse = Arc<dyn Fn(&InputData) -> Parsed + Send + Sync>;
+pub type ParsedPart = Arc<dyn Fn(&(dyn Any + Send + Sync)) -> Answer + Send + Sync>;
+
+/// The steps of a `Solution`, with the parsed value type-erased.
+pub struct Phases {
+    pub parse: Parse,
+    pub parts: [ParsedPart; 2],
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct SolutionKey {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+}
+
+pub struct Registry {
+    solutions: BTreeMap<SolutionKey, Part>,
+    phases: BTreeMap<(u16, u8), Phases>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self { solutions: BTreeMap::new(), phases: BTreeMap::new() }
+    }
+
+    pub fn register<T: Into<Answer>, F: Fn(&InputData) -> T + Send + Sync + 'static>(&mut self, year: u16, day: u8, part: u8, solution: F) {
+        let key = SolutionKey { year, day, part };
+        if self.solutions.insert(key, Arc::new(move |input| solution(input).into())).is_some() {
+            panic!("Solution for year {} day {} part {} registered twice", year, day, part);
+        }
+    }
+
+    /// Registers both parts of a day. Each part also works on its own, parsing the input itself.
+    pub fn register_solution<S: Solution + 'static>(&mut self, year: u16, day: u8) {
+        self.register(year, day, 1, |input: &InputData| S::part_1(&S::parse(input)).into());
+        self.register(year, day, 2, |input: &InputData| S::part_2(&S::parse(input)).into());
+        self.phases.insert((year, day), Phases {
+            parse: Arc::new(|input| Arc::new(S::parse(input))),
+            parts: [
+                Arc::new(|parsed| S::part_1(parsed.downcast_ref().unwrap()).into()),
+                Arc::new(|parsed| S::part_2(parsed.downcast_ref().unwrap()).into()),
+            ],
+        });
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: u8) -> Option<&Part> {
+        self.solutions.get(&SolutionKey { year, day, part })
+    }
+
+    /// The separate steps of a day, if it is registered as a `Solution`.
+    pub fn phases(&self, year: u16, day: u8) -> Option<&Phases> {
+        self.phases.get(&(year, day))
+    }
+
+    pub fn years(&self) -> impl Iterator<Item=u16> + '_ {
+        self.solutions.keys().map(|key| key.year).dedup()
+    }
+
+    pub fn days(&self, year: u16) -> impl Iterator<Item=u8> + '_ {
+        self.solutions.keys()
+            .filter(move |key| key.year == year)
+            .map(|key| key.day)
+            .dedup()
+    }
+
+    pub fn missing_days(&self, year: u16) -> impl Iterator<Item=u8> + '_ {
+        (1..=25).filter(move |&day| self.get(year, day, 1).is_none())
+    }
+}
+
+/// Declares the day modules of a year. A day is either `1 => day01`, with free `part_1` and `part_2`
+/// functions, or `1 => day01::Day01`, naming a type that implements `Solution`.
+#[macro_export]
+macro_rules! solutions {
+    ($year:literal; $($number:literal => $day:ident $(:: $solution:ident)?),* $(,)?) => {
+        $(pub mod $day;)*
+
+        pub fn register(registry: &mut $crate::registry::Registry) {
+            $($crate::register_day!(registry, $year, $number, $day $(:: $solution)?);)*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! register_day {
+    ($registry:ident, $year:literal, $number:literal, $day:ident) => {
+        $registry.register($year, $number, 1, $day::part_1);
+        $registry.register($year, $number, 2, $day::part_2);
+    };
+    ($registry:ident, $year:literal, $number:literal, $day:ident :: $solution:ident) => {
+        $registry.register_solution::<$day::$solution>($year, $number);
+    };
+}
+
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn solutions_can_be_looked_up() {
+        let registry = registry();
+
+        let result = registry.get(2021, 2, 1).unwrap()(&InputData::from_string("abc"));
+
+        assert_eq!(result, Answer::Number(3));
+    }
+
+    #[test]
+    fn unknown_solutions_are_not_found() {
+        let registry = registry();
+
+        assert!(registry.get(2021, 2, 3).is_none());
+        assert!(registry.get(2021, 3, 1).is_none());
+        assert!(registry.get(2022, 2, 1).is_none());
+    }
+
+    #[test]
+    fn solution_phases_are_registered() {
+        let registry = registry();
+
+        let phases = registry.phases(2022, 3).unwrap();
+        let parsed = (phases.parse)(&InputData::from_string("abc"));
+
+        assert_eq!((phases.parts[0])(parsed.as_ref()), Answer::Number(3));
+        assert_eq!((phases.parts[1])(parsed.as_ref()), Answer::Text("abc".to_owned()));
+        assert_eq!(registry.get(2022, 3, 2).unwrap()(&InputData::from_string("xy")), Answer::Text("xy".to_owned()));
+        assert!(registry.phases(2021, 2).is_none());
+    }
+
+    #[test]
+    fn days_are_listed_in_order() {
+        let registry = registry();
+
+        assert_eq!(registry.years().collect_vec(), vec![2021, 2022]);
+        assert_eq!(registry.days(2021).collect_vec(), vec![1, 2]);
+    }
+
+    #[test]
+    fn missing_days_are_reported() {
+        let registry = registry();
+
+        assert_eq!(registry.missing_days(2022).collect_vec(), (2..=25).filter(|&day| day != 3).collect_vec());
+    }
+
+    struct Length;
+
+    impl Solution for Length {
+        type Parsed = String;
+        type Part1 = usize;
+        type Part2 = String;
+
+        fn parse(input: &InputData) -> String {
+            String::from_utf8(input.raw().to_vec()).unwrap()
+        }
+
+        fn part_1(parsed: &String) -> usize {
+            parsed.len()
+        }
+
+        fn part_2(parsed: &String) -> String {
+            parsed.clone()
+        }
+    }
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry.register(2021, 2, 2, |input: &InputData| input.len() * 2);
+        registry.register(2021, 2, 1, |input: &InputData| input.len());
+        registry.register(2021, 1, 1, |_: &InputData| "first");
+        registry.register(2022, 1, 1, |_: &InputData| 0);
+        registry.register_solution::<Length>(2022, 3);
+        registry
+    }
+}
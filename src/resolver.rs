@@ -0,0 +1,164 @@
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+
+use itertools::Itertools;
+
+use crate::config::Config;
+use crate::input::InputData;
+
+pub const INPUT_PATH_VARIABLE: &str = "AOC_INPUT_PATH";
+pub const USER_VARIABLE: &str = "AOC_USER";
+pub const STDIN_PATH: &str = "-";
+
+const DEFAULT_PATH: &str = "input/year{year}/day{day}";
+/// The layout used before there was more than one year, only searched for 2021.
+const LEGACY_2021_PATH: &str = "inputs/{day}.in";
+
+/// Finds the input of a day by trying a list of path templates in order. Templates can refer to
+/// `{year}`, `{day}` (always two digits) and `{user}`, and `-` stands for stdin.
+pub struct InputResolver {
+    search_paths: Vec<String>,
+    user: Option<String>,
+    use_legacy_path: bool,
+}
+
+impl InputResolver {
+    pub fn new(search_paths: Vec<String>, user: Option<String>) -> Self {
+        Self { search_paths, user, use_legacy_path: false }
+    }
+
+    /// Search paths come from `AOC_INPUT_PATH` (separated like `PATH`), or from the `input_path` entries
+    /// of the config file. The user comes from `AOC_USER`, the `user` config entry or `USER`.
+    pub fn from_config(config: &Config) -> Self {
+        let user = config.get(USER_VARIABLE, "user").or_else(|| env::var("USER").ok());
+        let search_paths: Vec<String> = if let Some(variable) = env::var_os(INPUT_PATH_VARIABLE) {
+            env::split_paths(&variable).map(|path| path.to_string_lossy().into_owned()).collect()
+        } else {
+            config.get_all("input_path").map(str::to_owned).collect()
+        };
+        if search_paths.is_empty() {
+            Self { search_paths: vec![DEFAULT_PATH.to_owned()], user, use_legacy_path: true }
+        } else {
+            Self::new(search_paths, user)
+        }
+    }
+
+    pub fn resolve(&self, year: u16, day: u8) -> Result<InputData, InputError> {
+        let candidates = self.candidates(year, day);
+        candidates.iter()
+            .find(|path| *path == STDIN_PATH || Path::new(path).exists())
+            .map(|path| read_input(path))
+            .unwrap_or_else(|| Err(InputError::NotFound { year, day, tried: candidates.clone() }))
+    }
+
+    /// The first candidate file that already exists, stdin aside.
+    pub fn cached_location(&self, year: u16, day: u8) -> Option<String> {
+        self.candidates(year, day).into_iter()
+            .find(|path| path != STDIN_PATH && Path::new(path).exists())
+    }
+
+    /// Where a downloaded input goes, which is the first candidate that is not stdin.
+    pub fn download_location(&self, year: u16, day: u8) -> Option<String> {
+        self.candidates(year, day).into_iter()
+            .find(|path| path != STDIN_PATH)
+    }
+
+    pub fn candidates(&self, year: u16, day: u8) -> Vec<String> {
+        let legacy_path = Some(LEGACY_2021_PATH).filter(|_| self.use_legacy_path && year == 2021);
+        self.search_paths.iter()
+            .map(String::as_str)
+            .chain(legacy_path)
+            .map(|template| {
+                let path = template
+                    .replace("{year}", &year.to_string())
+                    .replace("{day}", &format!("{:02}", day));
+                match &self.user {
+                    Some(user) => path.replace("{user}", user),
+                    None => path,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Reads the input from a file, or from stdin if the path is `-`.
+pub fn read_input(path: &str) -> Result<InputData, InputError> {
+    let result = if path == STDIN_PATH {
+        let mut data = Vec::new();
+        io::stdin().read_to_end(&mut data).map(|_| data)
+    } else {
+        fs::read(path)
+    };
+    result
+        .map(InputData::from_bytes)
+        .map_err(|error| InputError::Unreadable { path: path.to_owned(), error })
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    NotFound { year: u16, day: u8, tried: Vec<String> },
+    Unreadable { path: String, error: io::Error },
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::NotFound { year, day, tried } => write!(f, "No input for year {} day {:02}, tried {}", year, day, tried.iter().join(", ")),
+            InputError::Unreadable { path, error } => write!(f, "Could not read {}: {}", path, error),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::TempDirectory;
+
+    use super::*;
+
+    #[test]
+    fn first_existing_candidate_is_used() {
+        let directory = TempDirectory::new("resolver_first_existing");
+        fs::create_dir_all(directory.join("bob")).unwrap();
+        fs::write(directory.join("bob/2021-05"), "bob's input").unwrap();
+        fs::write(directory.join("shared-2021-05"), "shared input").unwrap();
+
+        for (user, expected) in [("bob", "bob's input"), ("alice", "shared input")] {
+            let resolver = InputResolver::new(
+                vec![
+                    format!("{}/{{user}}/{{year}}-{{day}}", directory.display()),
+                    format!("{}/shared-{{year}}-{{day}}", directory.display()),
+                ],
+                Some(user.to_owned()),
+            );
+
+            let result = resolver.resolve(2021, 5).unwrap();
+
+            assert_eq!(result.raw(), expected.as_bytes());
+        }
+    }
+
+    #[test]
+    fn missing_input_lists_tried_paths() {
+        let resolver = InputResolver::new(
+            vec!["nowhere/{user}/year{year}/day{day}".to_owned(), "nowhere/{day}.txt".to_owned()],
+            Some("alice".to_owned()),
+        );
+
+        let result = resolver.resolve(2022, 7).err().unwrap();
+
+        assert_eq!(result.to_string(), "No input for year 2022 day 07, tried nowhere/alice/year2022/day07, nowhere/07.txt");
+    }
+
+    #[test]
+    fn legacy_path_is_only_used_for_2021() {
+        let resolver = InputResolver { search_paths: vec![DEFAULT_PATH.to_owned()], user: None, use_legacy_path: true };
+
+        assert_eq!(resolver.candidates(2021, 3), vec!["input/year2021/day03", "inputs/03.in"]);
+        assert_eq!(resolver.candidates(2022, 3), vec!["input/year2022/day03"]);
+    }
+}
@@ -0,0 +1,155 @@
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE_FILE: &str = "template";
+const MAIN_FILE: &str = "src/main.rs";
+
+/// Creates `src/yearXXXX/dayNN.rs` from the template and adds it to the year's `solutions!` list,
+/// creating and registering the year module first if needed. Returns the files that were written.
+pub fn new_day(root: &Path, year: u16, day: u8) -> io::Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        return Err(io::Error::new(ErrorKind::InvalidInput, format!("There is no day {}", day)));
+    }
+    let year_module = format!("year{}", year);
+    let day_module = format!("day{:02}", day);
+    let day_path = root.join("src").join(&year_module).join(format!("{}.rs", day_module));
+    let year_path = root.join("src").join(format!("{}.rs", year_module));
+    let main_path = root.join(MAIN_FILE);
+
+    if day_path.exists() {
+        return Err(io::Error::new(ErrorKind::AlreadyExists, format!("{} already exists", day_path.display())));
+    }
+    let template = fs::read_to_string(root.join(TEMPLATE_FILE))?;
+    let new_main = if year_path.exists() {
+        None
+    } else {
+        Some(register_year(&fs::read_to_string(&main_path)?, &year_module).ok_or_else(|| malformed(&main_path))?)
+    };
+    let year_source = if year_path.exists() {
+        fs::read_to_string(&year_path)?
+    } else {
+        format!("crate::solutions!({};\n);\n", year)
+    };
+    let new_year_source = register_day(&year_source, day).ok_or_else(|| malformed(&year_path))?;
+
+    let mut written = Vec::new();
+    fs::create_dir_all(day_path.parent().unwrap())?;
+    fs::write(&day_path, template)?;
+    written.push(day_path);
+    fs::write(&year_path, new_year_source)?;
+    written.push(year_path);
+    if let Some(new_main) = new_main {
+        fs::write(&main_path, new_main)?;
+        written.push(main_path);
+    }
+    Ok(written)
+}
+
+fn malformed(path: &Path) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, format!("Could not find where to register the new module in {}", path.display()))
+}
+
+fn register_day(year_source: &str, day: u8) -> Option<String> {
+    let entry = format!("    {} => day{:02},", day, day);
+    insert_entry(year_source, "crate::solutions!(", &entry, |line| {
+        let (number, _) = line.trim().split_once(" => ")?;
+        number.parse::<u8>().ok()
+    }, day)
+}
+
+fn register_year(main_source: &str, year_module: &str) -> Option<String> {
+    let entry = format!("    {},", year_module);
+    insert_entry(main_source, "years!(", &entry, |line| Some(line.trim().trim_end_matches(',').to_owned()), year_module.to_owned())
+}
+
+/// Inserts `entry` into the sorted list of a macro invocation, refusing to insert duplicates.
+fn insert_entry<K: Ord, F: Fn(&str) -> Option<K>>(source: &str, invocation: &str, entry: &str, key: F, new_key: K) -> Option<String> {
+    let mut lines: Vec<&str> = source.lines().collect();
+    let start = lines.iter().position(|line| line.starts_with(invocation))? + 1;
+    let end = start + lines[start..].iter().position(|line| line.starts_with(");"))?;
+    let mut position = end;
+    for (i, line) in lines.iter().enumerate().take(end).skip(start) {
+        match key(line) {
+            Some(existing) if existing == new_key => return None,
+            Some(existing) if existing > new_key => {
+                position = i;
+                break;
+            }
+            _ => {}
+        }
+    }
+    lines.insert(position, entry);
+    Some(lines.join("\n") + "\n")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::input::unindent;
+
+    use super::*;
+
+    #[test]
+    fn days_are_registered_in_order() {
+        let year_source = unindent("
+            crate::solutions!(2022;
+                1 => day01,
+                3 => day03,
+            );
+        ");
+
+        let result = register_day(&year_source, 2).unwrap();
+
+        assert_eq!(result, unindent("
+            crate::solutions!(2022;
+                1 => day01,
+                2 => day02,
+                3 => day03,
+            );
+        "));
+    }
+
+    #[test]
+    fn days_are_registered_in_empty_year() {
+        let result = register_day("crate::solutions!(2022;\n);\n", 12).unwrap();
+
+        assert_eq!(result, "crate::solutions!(2022;\n    12 => day12,\n);\n");
+    }
+
+    #[test]
+    fn registered_day_is_not_registered_again() {
+        let result = register_day("crate::solutions!(2022;\n    12 => day12,\n);\n", 12);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn years_are_registered_in_order() {
+        let main_source = unindent("
+            mod input;
+
+            years!(
+                year2015,
+                year2021,
+            );
+
+            fn main() {}
+        ");
+
+        let result = register_year(&main_source, "year2016").unwrap();
+
+        assert_eq!(result, unindent("
+            mod input;
+
+            years!(
+                year2015,
+                year2016,
+                year2021,
+            );
+
+            fn main() {}
+        "));
+    }
+}
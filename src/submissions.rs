@@ -0,0 +1,278 @@
+use std::fmt::{Display, Formatter, Write};
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+
+use crate::answer::Answer;
+use crate::answer_store::escape;
+use crate::registry::SolutionKey;
+
+pub const SUBMISSIONS_FILE: &str = "submissions.txt";
+
+/// What the site said about a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// The answer was not looked at because the previous one was too recent; seconds to wait.
+    Wait(u64),
+    /// The part has already been solved, so the answer was not looked at either.
+    AlreadySolved,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Right => f.write_str("right"),
+            Verdict::Wrong => f.write_str("wrong"),
+            Verdict::TooHigh => f.write_str("too-high"),
+            Verdict::TooLow => f.write_str("too-low"),
+            Verdict::Wait(seconds) => write!(f, "wait-{}", seconds),
+            Verdict::AlreadySolved => f.write_str("already-solved"),
+        }
+    }
+}
+
+impl Verdict {
+    fn parse(value: &str) -> Option<Self> {
+        Some(match value {
+            "right" => Verdict::Right,
+            "wrong" => Verdict::Wrong,
+            "too-high" => Verdict::TooHigh,
+            "too-low" => Verdict::TooLow,
+            "already-solved" => Verdict::AlreadySolved,
+            _ => Verdict::Wait(value.strip_prefix("wait-")?.parse().ok()?),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Submission {
+    /// Seconds since the Unix epoch.
+    pub time: u64,
+    pub key: SolutionKey,
+    pub answer: Answer,
+    pub verdict: Verdict,
+    /// Seconds the site asked to wait before the next attempt, on top of a `Wait` verdict.
+    pub wait: u64,
+}
+
+/// Why an answer is not worth submitting.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Refusal {
+    NothingToSubmit,
+    AlreadySolved(Answer),
+    AlreadyRejected(Verdict),
+    AtLeast(i128),
+    AtMost(i128),
+    RateLimited(u64),
+}
+
+impl Display for Refusal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Refusal::NothingToSubmit => write!(f, "There is nothing to submit"),
+            Refusal::AlreadySolved(answer) => write!(f, "Already solved, the answer was {}", escape(answer)),
+            Refusal::AlreadyRejected(verdict) => write!(f, "This answer was already submitted, it was {}", verdict),
+            Refusal::AtLeast(bound) => write!(f, "{} was too high, so this one is too", bound),
+            Refusal::AtMost(bound) => write!(f, "{} was too low, so this one is too", bound),
+            Refusal::RateLimited(seconds) => write!(f, "Submitted too recently, wait {}s", seconds),
+        }
+    }
+}
+
+/// Every answer ever submitted, in the order they were submitted, one per line in the form
+/// `<time> <year> <day> <part> <verdict>[/<wait>] <answer>`.
+pub struct SubmissionLog {
+    submissions: Vec<Submission>,
+}
+
+impl SubmissionLog {
+    pub fn load(path: &str) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Self::parse(&contents)
+                .map_err(|line| io::Error::new(ErrorKind::InvalidData, format!("Malformed line in {}: {}", path, line))),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Self { submissions: Vec::new() }),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        fs::write(path, self.serialize())
+    }
+
+    pub fn push(&mut self, submission: Submission) {
+        self.submissions.push(submission);
+    }
+
+    /// Checks `answer` against what earlier submissions of the same part revealed.
+    pub fn check(&self, key: SolutionKey, answer: &Answer, now: u64) -> Result<(), Refusal> {
+        if *answer == Answer::NoPart2 {
+            return Err(Refusal::NothingToSubmit);
+        }
+        let number = as_number(answer);
+        for submission in self.submissions.iter().filter(|submission| submission.key == key) {
+            let previous = as_number(&submission.answer);
+            match submission.verdict {
+                Verdict::Right => return Err(Refusal::AlreadySolved(submission.answer.clone())),
+                Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow if submission.answer == *answer => {
+                    return Err(Refusal::AlreadyRejected(submission.verdict));
+                }
+                Verdict::TooHigh => if let (Some(number), Some(bound)) = (number, previous) {
+                    if number >= bound {
+                        return Err(Refusal::AtLeast(bound));
+                    }
+                },
+                Verdict::TooLow => if let (Some(number), Some(bound)) = (number, previous) {
+                    if number <= bound {
+                        return Err(Refusal::AtMost(bound));
+                    }
+                },
+                _ => {}
+            }
+            let wait = match submission.verdict {
+                Verdict::Wait(seconds) => seconds,
+                _ => submission.wait,
+            };
+            if submission.time + wait > now {
+                return Err(Refusal::RateLimited(submission.time + wait - now));
+            }
+        }
+        Ok(())
+    }
+
+    fn parse(contents: &str) -> Result<Self, &str> {
+        let mut submissions = Vec::new();
+        for line in contents.lines().filter(|line| !line.is_empty()) {
+            submissions.push(Self::parse_line(line).ok_or(line)?);
+        }
+        Ok(Self { submissions })
+    }
+
+    fn parse_line(line: &str) -> Option<Submission> {
+        let mut fields = line.splitn(6, ' ');
+        let time = fields.next()?.parse().ok()?;
+        let key = SolutionKey {
+            year: fields.next()?.parse().ok()?,
+            day: fields.next()?.parse().ok()?,
+            part: fields.next()?.parse().ok()?,
+        };
+        let verdict = fields.next()?;
+        let (verdict, wait) = match verdict.split_once('/') {
+            Some((verdict, wait)) => (verdict, wait.parse().ok()?),
+            None => (verdict, 0),
+        };
+        let verdict = Verdict::parse(verdict)?;
+        let answer = fields.next()?.replace("\\n", "\n").parse().ok()?;
+        Some(Submission { time, key, answer, verdict, wait })
+    }
+
+    fn serialize(&self) -> String {
+        let mut result = String::new();
+        for submission in &self.submissions {
+            write!(
+                result,
+                "{} {} {:02} {} {}",
+                submission.time,
+                submission.key.year,
+                submission.key.day,
+                submission.key.part,
+                submission.verdict,
+            ).unwrap();
+            if submission.wait > 0 {
+                write!(result, "/{}", submission.wait).unwrap();
+            }
+            writeln!(result, " {}", escape(&submission.answer)).unwrap();
+        }
+        result
+    }
+}
+
+fn as_number(answer: &Answer) -> Option<i128> {
+    match answer {
+        Answer::Number(number) => Some(*number),
+        Answer::Text(text) => text.parse().ok(),
+        Answer::NoPart2 => None,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: SolutionKey = SolutionKey { year: 2021, day: 7, part: 1 };
+
+    #[test]
+    fn serialized_log_can_be_parsed_back() {
+        let mut log = log(vec![
+            (100, Answer::from(1000u64), Verdict::TooHigh),
+            (200, Answer::from(900u64), Verdict::Wait(45)),
+            (300, Answer::from("ab\ncd"), Verdict::Right),
+        ]);
+        log.submissions[0].wait = 60;
+
+        let serialized = log.serialize();
+        let parsed = SubmissionLog::parse(&serialized).unwrap();
+
+        assert_eq!(serialized, "100 2021 07 1 too-high/60 1000\n200 2021 07 1 wait-45 900\n300 2021 07 1 right ab\\ncd\n");
+        assert_eq!(parsed.submissions, log.submissions);
+    }
+
+    #[test]
+    fn rejected_answers_are_not_submitted_again() {
+        let log = log(vec![(100, Answer::from("abc"), Verdict::Wrong)]);
+
+        assert_eq!(log.check(KEY, &Answer::from("abc"), 1000), Err(Refusal::AlreadyRejected(Verdict::Wrong)));
+        assert_eq!(log.check(KEY, &Answer::from("abd"), 1000), Ok(()));
+        assert_eq!(log.check(SolutionKey { part: 2, ..KEY }, &Answer::from("abc"), 1000), Ok(()));
+    }
+
+    #[test]
+    fn answers_outside_bounds_are_not_submitted() {
+        let log = log(vec![
+            (100, Answer::from(1000u64), Verdict::TooHigh),
+            (200, Answer::from(500u64), Verdict::TooLow),
+        ]);
+
+        assert_eq!(log.check(KEY, &Answer::from(1200u64), 1000), Err(Refusal::AtLeast(1000)));
+        assert_eq!(log.check(KEY, &Answer::from(500u64), 1000), Err(Refusal::AlreadyRejected(Verdict::TooLow)));
+        assert_eq!(log.check(KEY, &Answer::from(499u64), 1000), Err(Refusal::AtMost(500)));
+        assert_eq!(log.check(KEY, &Answer::from(750u64), 1000), Ok(()));
+    }
+
+    #[test]
+    fn waiting_is_respected() {
+        let log = log(vec![(100, Answer::from(750u64), Verdict::Wait(60))]);
+
+        assert_eq!(log.check(KEY, &Answer::from(750u64), 130), Err(Refusal::RateLimited(30)));
+        assert_eq!(log.check(KEY, &Answer::from(750u64), 160), Ok(()));
+    }
+
+    #[test]
+    fn waiting_after_rejection_is_respected() {
+        let mut log = log(vec![(100, Answer::from(750u64), Verdict::Wrong)]);
+        log.submissions[0].wait = 300;
+
+        assert_eq!(log.check(KEY, &Answer::from(751u64), 130), Err(Refusal::RateLimited(270)));
+        assert_eq!(log.check(KEY, &Answer::from(750u64), 130), Err(Refusal::AlreadyRejected(Verdict::Wrong)));
+        assert_eq!(log.check(KEY, &Answer::from(751u64), 400), Ok(()));
+    }
+
+    #[test]
+    fn solved_parts_are_not_submitted_again() {
+        let log = log(vec![(100, Answer::from(750u64), Verdict::Right)]);
+
+        assert_eq!(log.check(KEY, &Answer::from(751u64), 1000), Err(Refusal::AlreadySolved(Answer::from(750u64))));
+    }
+
+    fn log(submissions: Vec<(u64, Answer, Verdict)>) -> SubmissionLog {
+        SubmissionLog {
+            submissions: submissions.into_iter()
+                .map(|(time, answer, verdict)| Submission { time, key: KEY, answer, verdict, wait: 0 })
+                .collect(),
+        }
+    }
+}
@@ -24,14 +24,13 @@ struct ProcessedBoard {
 }
 
 fn score_boards(input: &InputData) -> impl Iterator<Item=ProcessedBoard> + '_ {
-    let mut lines = input.lines().peekable();
-    let turn_per_number: AHashMap<_, _> = lines.next().unwrap().stream().parse_iter::<u64>(",")
+    let mut sections = input.sections();
+    let turn_per_number: AHashMap<_, _> = sections.next().unwrap().stream().parse_iter::<u64>(",")
         .enumerate_as_second()
         .collect();
-    lines.next();
-    lines
-        .map_chunks(move |chunk| {
-            chunk.into_iter().map(|line| line.stream().parse_iter_right_aligned::<u64>()
+    sections
+        .map(move |section| {
+            section.lines().map(|line| line.stream().parse_iter_whitespace::<u64>()
                 .map(|number| (number, turn_per_number[&number]))
                 .collect_vec()
             ).collect::<Array2d<(u64, usize)>>()
@@ -4,7 +4,7 @@ use derive_more::{BitAnd, BitOr};
 use itertools::Itertools;
 use parse_yolo_derive::ParseYolo;
 
-use crate::input::{InputData, IteratorExtras, ParseStream, ParseYolo};
+use crate::input::{Expected, InputData, IteratorExtras, ParseError, ParseStream, ParseYolo};
 
 pub fn part_1(input: &InputData) -> usize {
     input.lines_as::<PuzzleInput>()
@@ -96,11 +96,12 @@ impl SegmentSet {
     }
 }
 
-impl ParseYolo<'_> for SegmentSet {
-    fn parse_from_stream(stream: &mut ParseStream) -> Result<Self, ()> {
+impl<'a> ParseYolo<'a> for SegmentSet {
+    fn parse_from_stream(stream: &mut ParseStream<'a>) -> Result<Self, ParseError<'a>> {
         Ok(
             Self(
                 stream.fold_while(
+                    Expected::Described("segments a to g"),
                     0,
                     |c| (b'a'..=b'g').contains(&c),
                     |acc, c| acc | 1 << (c - b'a'),
@@ -1,10 +1,9 @@
 use ahash::HashSet;
-use bstr::ByteSlice;
 use itertools::Itertools;
 use parse_yolo_derive::ParseYolo;
 
 use crate::array::Coordinate2d;
-use crate::input::{InputData, OrdIteratorExtras, ParseStream, ParseYolo};
+use crate::input::{InputData, OrdIteratorExtras, ParseYolo};
 
 pub fn part_1(input: &InputData) -> usize {
     let (instructions, dots) = parse_input(input);
@@ -40,9 +39,9 @@ pub fn part_2(input: &InputData) -> String {
 }
 
 fn parse_input(input: &InputData) -> (Vec<Fold>, impl Iterator<Item=Coordinate2d> + '_) {
-    let (unparsed_dots, instructions) = input.raw().split_once_str("\n\n").unwrap();
-    let instructions = ParseStream::new(instructions).parse_iter::<Fold>("\n").collect_vec();
-    let dots = ParseStream::new(unparsed_dots).parse_iter::<Dot>("\n")
+    let [dots, instructions] = input.split_sections();
+    let instructions = instructions.stream().parse_iter::<Fold>("\n").collect_vec();
+    let dots = dots.stream().parse_iter::<Dot>("\n")
         .map(|dot| dot.to_coordinate());
     (instructions, dots)
 }
@@ -16,11 +16,11 @@ pub fn part_2(input: &InputData) -> usize {
 }
 
 fn run(input: &InputData, steps: usize) -> usize {
-    let mut stream = input.stream();
-    let template: Word = stream.parse_header();
+    let [template, rules_section] = input.split_sections();
+    let template: Word = template.stream().parse_yololo();
     let mut indexer: HashIndexer<Pair> = HashIndexer::new();
     let mut rules: U8Map<[u8; 2]> = U8Map::new();
-    for rule in stream.parse_iter::<InsertionRule>("\n") {
+    for rule in rules_section.stream().parse_iter::<InsertionRule>("\n") {
         let pair = Pair::from_bytes(rule.pair.as_bytes());
         let rule_index = indexer.get_or_insert(pair) as u8;
         let children = pair.insert(rule.new_element as u8);
@@ -99,7 +99,7 @@ impl BitIterator {
             'F' => 15,
         );
         let hex_values = input.raw();
-        let mut values = vec![0u8; (hex_values.len() + 1) / 2];
+        let mut values = vec![0u8; hex_values.len().div_ceil(2)];
         for i in 0..hex_values.len() {
             let digit_value = hex_digit_values.get(hex_values[i]);
             if (i & 1) == 0 {
@@ -12,39 +12,48 @@ use parse_yolo_derive::ParseYolo;
 
 use crate::array::Vector3d;
 use crate::input::{InputData, U8IteratorExtras, U8SliceExtras};
+use crate::registry::Solution;
 
 const MIN_OVERLAP: usize = 12;
 
-pub fn part_1(input: &InputData) -> usize {
-    let processed_scanners = process_scanners(input);
+pub struct Day19;
 
-    let mut num_beacons = processed_scanners[0].report.beacons.len();
+impl Solution for Day19 {
+    type Parsed = Vec<ProcessedScanner>;
+    type Part1 = usize;
+    type Part2 = u32;
 
-    for i in 1..processed_scanners.len() {
-        num_beacons += processed_scanners[i].report.beacons.iter()
-            .filter(|beacon| !processed_scanners.iter().take(i).any(|scanner| scanner.contains(beacon)))
-            .count();
+    fn parse(input: &InputData) -> Vec<ProcessedScanner> {
+        process_scanners(input)
     }
 
-    num_beacons
-}
+    fn part_1(processed_scanners: &Vec<ProcessedScanner>) -> usize {
+        let mut num_beacons = processed_scanners[0].report.beacons.len();
 
-pub fn part_2(input: &InputData) -> u32 {
-    let processed_scanners = process_scanners(input);
-    let mut max_distance = 0;
-    for i in 0..processed_scanners.len() {
-        for j in 0..i {
-            max_distance = max(max_distance, processed_scanners[i].scanner_position.manhattan_distance(&processed_scanners[j].scanner_position));
+        for i in 1..processed_scanners.len() {
+            num_beacons += processed_scanners[i].report.beacons.iter()
+                .filter(|beacon| !processed_scanners.iter().take(i).any(|scanner| scanner.contains(beacon)))
+                .count();
         }
+
+        num_beacons
+    }
+
+    fn part_2(processed_scanners: &Vec<ProcessedScanner>) -> u32 {
+        let mut max_distance = 0;
+        for i in 0..processed_scanners.len() {
+            for j in 0..i {
+                max_distance = max(max_distance, processed_scanners[i].scanner_position.manhattan_distance(&processed_scanners[j].scanner_position));
+            }
+        }
+        max_distance
     }
-    max_distance
 }
 
 fn process_scanners(input: &InputData) -> Vec<ProcessedScanner> {
-    let mut scanner_reports = input.lines()
-        .map_chunks(|mut chunk| {
-            chunk.next();
-            let beacons = chunk.map(|line| line.stream().parse_yololo::<BeaconPosition>()).map(|beacon| beacon.as_vector()).collect_vec();
+    let mut scanner_reports = input.sections()
+        .map(|section| {
+            let beacons = section.lines().skip(1).map(|line| line.stream().parse_yololo::<BeaconPosition>()).map(|beacon| beacon.as_vector()).collect_vec();
             let mut graph = HashMap::with_capacity(beacons.len() * (beacons.len() - 1) / 2);
             for i in 0..beacons.len() {
                 for j in 0..i {
@@ -117,7 +126,7 @@ fn find_match(processed_scanner: &ProcessedScanner, scanner_report: &ScannerRepo
         }
     }
 
-    return None;
+    None
 }
 
 fn try_match<'a>(beacon_1: &'a Vector3d, beacon_2: &'a Vector3d, beacon_3: &'a Vector3d, beacon_4: &'a Vector3d) -> Option<(&'a Vector3d, &'a Vector3d)> {
@@ -196,14 +205,16 @@ impl CoordinateTransformation {
     }
 }
 
+type BeaconPair<'a> = (&'a Vector3d, &'a Vector3d);
+
 #[derive(new)]
-struct ProcessedScanner {
+pub struct ProcessedScanner {
     scanner_position: Vector3d,
     report: ScannerReport,
 }
 
 impl ProcessedScanner {
-    pub fn intersection<'a, 'b>(&'a self, other: &'b ScannerReport) -> impl Iterator<Item=((&'a DistanceInvariant, ((&'a Vector3d, &'a Vector3d), (&'b Vector3d, &'b Vector3d))))> {
+    fn intersection<'a, 'b>(&'a self, other: &'b ScannerReport) -> impl Iterator<Item=(&'a DistanceInvariant, (BeaconPair<'a>, BeaconPair<'b>))> {
         self.report.graph.iter()
             .filter_map(|(distance, &(my_beacon_index_1, my_beacon_index_2))|
                 other.graph.get(distance).map(|&(other_beacon_index_1, other_beacon_index_2)| {
@@ -219,7 +230,7 @@ impl ProcessedScanner {
     }
 
     pub fn contains(&self, beacon: &Vector3d) -> bool {
-        (beacon - &self.scanner_position).iter().all(|it| it >= -1000 && it <= 1000)
+        (beacon - &self.scanner_position).iter().all(|it| (-1000..=1000).contains(&it))
     }
 }
 
@@ -232,14 +243,14 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = part_1(&data());
+        let result = Day19::part_1(&Day19::parse(&data()));
 
         assert_eq!(result, 79);
     }
 
     #[test]
     fn part_2_works() {
-        let result = part_2(&data());
+        let result = Day19::part_2(&Day19::parse(&data()));
 
         assert_eq!(result, 3621);
     }
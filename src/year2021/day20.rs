@@ -1,4 +1,3 @@
-use bstr::ByteSlice;
 use itertools::Itertools;
 
 use crate::input::{DefaultIteratorExtras, InputData};
@@ -12,9 +11,9 @@ pub fn part_2(input: &InputData) -> usize {
 }
 
 fn enhance(input: &InputData, iterations: usize) -> usize {
-    let mut lines = input.lines().peekable();
-    let algorithm: [u8; 512] = lines.next().unwrap().iter().copied().map(parse_pixel).collect_array();
-    lines.next();
+    let [algorithm, image] = input.split_sections();
+    let algorithm: [u8; 512] = algorithm.raw().iter().copied().map(parse_pixel).collect_array();
+    let mut lines = image.lines().peekable();
 
     let base_width = lines.peek().unwrap().len();
     let padding = iterations + 1;
@@ -28,7 +27,7 @@ fn enhance(input: &InputData, iterations: usize) -> usize {
         }
     }
     if base_height > base_width {
-        dark_image.extend(std::iter::repeat(0).take((base_height - base_width) * final_width));
+        dark_image.extend(std::iter::repeat_n(0, (base_height - base_width) * final_width));
     }
     let final_height = base_height + 2 * padding;
     let mut light_image = vec![algorithm[0]; final_width * final_height];
@@ -4,25 +4,40 @@ use ahash::{HashMap, HashMapExt};
 
 use crate::graph::shortest_path;
 use crate::input::{DefaultIteratorExtras, InputData};
+use crate::registry::Solution;
 
 const ENERGY_COSTS: [usize; 4] = [1, 10, 100, 1000];
 
-pub fn part_1(input: &InputData) -> usize {
-    solve(input, &[])
-}
+pub struct Day23;
 
-pub fn part_2(input: &InputData) -> usize {
-    solve(
-        input,
-        &[
-            ['D', 'C', 'B', 'A'],
-            ['D', 'B', 'A', 'C'],
-        ],
-    )
+impl Solution for Day23 {
+    /// The top and bottom rows of the side rooms.
+    type Parsed = [[Amphipod; 4]; 2];
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &InputData) -> Self::Parsed {
+        let chars = input.raw();
+        [31, 45].map(|row_start| (0..4).map(|i| Amphipod::from_char(chars[row_start + 2 * i])).collect_array())
+    }
+
+    fn part_1(rows: &Self::Parsed) -> usize {
+        solve(rows, &[])
+    }
+
+    fn part_2(rows: &Self::Parsed) -> usize {
+        solve(
+            rows,
+            &[
+                ['D', 'C', 'B', 'A'],
+                ['D', 'B', 'A', 'C'],
+            ],
+        )
+    }
 }
 
-fn solve(input: &InputData, extra: &[[char; 4]]) -> usize {
-    let starting_state = State::from_input(input, extra);
+fn solve(rows: &[[Amphipod; 4]; 2], extra: &[[char; 4]]) -> usize {
+    let starting_state = State::new(rows, extra);
 
     shortest_path(
         starting_state,
@@ -39,11 +54,11 @@ struct State {
 }
 
 impl State {
-    pub fn from_input(input: &InputData, extra: &[[char; 4]]) -> Self {
+    pub fn new(rows: &[[Amphipod; 4]; 2], extra: &[[char; 4]]) -> Self {
         Self {
             hallway: Hallway::default(),
             side_rooms: (0..4)
-                .map(|i| SideRoom::from_input(input, i, extra))
+                .map(|i| SideRoom::new(rows, i, extra))
                 .collect_array(),
         }
     }
@@ -62,7 +77,7 @@ impl State {
                 let target_room_offset = Hallway::room_offset(target_room_index);
                 let target_room = &self.side_rooms[target_room_index];
                 if target_room.has_no_visitors() && self.hallway.is_path_free(starting_room_offset, target_room_offset) {
-                    let mut new_side_rooms = self.side_rooms.clone();
+                    let mut new_side_rooms = self.side_rooms;
                     new_side_rooms[i] = new_side_rooms[i].pop_top_visitor();
                     new_side_rooms[target_room_index] = new_side_rooms[target_room_index].plus_completed();
                     let reduced = Self {
@@ -82,7 +97,7 @@ impl State {
                 let target_room_offset = Hallway::room_offset(target_room_index);
                 let target_room = &self.side_rooms[target_room_index];
                 if target_room.has_no_visitors() && self.hallway.is_path_free(i, target_room_offset) {
-                    let mut new_side_rooms = self.side_rooms.clone();
+                    let mut new_side_rooms = self.side_rooms;
                     new_side_rooms[target_room_index] = new_side_rooms[target_room_index].plus_completed();
                     let reduced = Self {
                         hallway: self.hallway.remove(i),
@@ -120,7 +135,7 @@ impl State {
                     }
                     let path_length_to_hallway = 1 + side_room_depth - side_room.num_amphipods();
                     for target_tile in target_tiles {
-                        let mut new_side_rooms = self.side_rooms.clone();
+                        let mut new_side_rooms = self.side_rooms;
                         new_side_rooms[i] = new_side_rooms[i].pop_top_visitor();
                         let reduced = Self {
                             hallway: self.hallway.plus(top_visitor, target_tile),
@@ -191,11 +206,9 @@ struct SideRoom {
 }
 
 impl SideRoom {
-    fn from_input(input: &InputData, room_index: usize, extra: &[[char; 4]]) -> Self {
-        let chars = input.raw();
-        let top_position = 31 + 2 * room_index;
-        let bottom_amphipod = Amphipod::from_char(chars[top_position + 14]);
-        let top_amphipod = Amphipod::from_char(chars[top_position]);
+    fn new(rows: &[[Amphipod; 4]; 2], room_index: usize, extra: &[[char; 4]]) -> Self {
+        let top_amphipod = rows[0][room_index];
+        let bottom_amphipod = rows[1][room_index];
         let mut num_completed = 0;
         let mut visitors = VisitorStack::default();
         if bottom_amphipod.is_home_in(room_index) {
@@ -231,7 +244,7 @@ impl SideRoom {
 
     fn plus_completed(&self) -> Self {
         Self {
-            visitors: self.visitors.clone(),
+            visitors: self.visitors,
             num_completed: self.num_completed + 1,
         }
     }
@@ -263,7 +276,7 @@ impl VisitorStack {
         if self.length == 0 {
             None
         } else {
-            Some(Amphipod::from_int(((self.values >> (self.length - 1) * 2) & 3) as u64))
+            Some(Amphipod::from_int(((self.values >> ((self.length - 1) * 2)) & 3) as u64))
         }
     }
 
@@ -276,7 +289,7 @@ impl VisitorStack {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
-struct Amphipod(u8);
+pub struct Amphipod(u8);
 
 impl Amphipod {
     fn from_char(c: u8) -> Self {
@@ -305,14 +318,14 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let result = part_1(&data());
+        let result = Day23::part_1(&Day23::parse(&data()));
 
         assert_eq!(result, 12521);
     }
 
     #[test]
     fn part_2_works() {
-        let result = part_2(&data());
+        let result = Day23::part_2(&Day23::parse(&data()));
 
         assert_eq!(result, 44169);
     }
@@ -224,9 +224,7 @@ impl Expression {
                         }
                     }
                     BinaryOperation::Mul => {
-                        if *op1.as_ref() == Expression::Constant(modulus) {
-                            Expression::Constant(0)
-                        } else if *op2.as_ref() == Expression::Constant(modulus) {
+                        if *op1.as_ref() == Expression::Constant(modulus) || *op2.as_ref() == Expression::Constant(modulus) {
                             Expression::Constant(0)
                         } else {
                             panic!("Fuck")
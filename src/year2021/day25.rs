@@ -1,3 +1,4 @@
+use crate::answer::Answer;
 use crate::input::InputData;
 
 pub fn part_1(input: &InputData) -> usize {
@@ -15,8 +16,8 @@ pub fn part_1(input: &InputData) -> usize {
     num_steps + 1
 }
 
-pub fn part_2(_: &InputData) -> usize {
-    0
+pub fn part_2(_: &InputData) -> Answer {
+    Answer::NoPart2
 }
 
 
@@ -127,7 +128,7 @@ mod tests {
     fn part_2_works() {
         let result = part_2(&data());
 
-        assert_eq!(result, 0);
+        assert_eq!(result, Answer::NoPart2);
     }
 
     fn data() -> InputData {